@@ -0,0 +1,20 @@
+use std::env;
+
+/// Generate the C header of the functions in `src/ffi.rs`. The header is only written when its
+/// contents change.
+fn main() {
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    // cbindgen parses the whole crate, not only the C interface
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("Should have been able to read cbindgen.toml.");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Could not generate the C header.")
+        .write_to_file(format!("{}/include/aoc22.h", crate_dir));
+}
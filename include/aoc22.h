@@ -0,0 +1,34 @@
+#ifndef AOC22_H
+#define AOC22_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Solve a part (1 or 2) of a day on the `len` bytes of UTF-8 text pointed to by `input`
+ * (which does not need to be NUL-terminated).
+ *
+ * Returns the answer as a NUL-terminated string, to be released with `aoc22_free_answer`,
+ * or NULL if that part is not solved, the input is not valid UTF-8 or the solver failed.
+ *
+ * # Safety
+ *
+ * `input` must point to `len` readable bytes, or be NULL with `len == 0`.
+ */
+char *aoc22_solve(uint32_t day, uint32_t part, const uint8_t *input, size_t len);
+
+/**
+ * Release an answer returned by `aoc22_solve`. Does nothing on NULL.
+ *
+ * # Safety
+ *
+ * `answer` must come from `aoc22_solve` and not have been released yet.
+ */
+void aoc22_free_answer(char *answer);
+
+#endif  /* AOC22_H */
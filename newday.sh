@@ -1,16 +1,27 @@
-echo """use std::fs;
-const INPUT_FILE: &str = \"data/test${1}.txt\";
+cat >> src/day${1}.rs <<TEMPLATE
+use crate::input::Input;
+const INPUT_FILE: &str = "data/test${1}.txt";
 
-pub fn main() {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect(\"Should have been able to read the file.\");
+pub fn main() -> usize {
+    return solve(&Input::read(INPUT_FILE));
 }
 
-pub fn main_bonus() {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect(\"Should have been able to read the file.\");
-}""" >> src/day${1}.rs 
+pub fn solve(_contents: &Input) -> usize {
+    todo!()
+}
+
+pub fn main_bonus() -> usize {
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(_contents: &Input) -> usize {
+    todo!()
+}
+TEMPLATE
 
 touch data/test${1}.txt
 
-touch data/input${1}.txt
\ No newline at end of file
+touch data/input${1}.txt
+
+echo "Add 'pub mod day${1};' to src/lib.rs, then register solve and solve_bonus in
+src/solutions.rs once they work, so that the day appears in submit, serve and the C interface."
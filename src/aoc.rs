@@ -0,0 +1,174 @@
+use std::{env, fmt, fs, io};
+use std::path::{Path, PathBuf};
+
+use crate::input;
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2022";
+/// Environment variable holding the `session` cookie of the website
+const SESSION_VAR: &str = "AOC_SESSION";
+/// Environment variable overriding the base URL, e.g. to point to a local server
+const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const DATA_DIR: &str = "data";
+const USER_AGENT: &str = "github.com/tremelow/advent-of-code-2022";
+
+#[derive(Debug)]
+pub enum ClientError {
+    InvalidDay(u32),
+    MissingSession,
+    Status(u16, String),
+    Transport(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::InvalidDay(day) => write!(f, "there is no day {} in the advent calendar", day),
+            ClientError::MissingSession => write!(f, "{} is not set", SESSION_VAR),
+            ClientError::Status(code, url) => write!(f, "{} answered with status {}", url, code),
+            ClientError::Transport(msg) => write!(f, "request failed: {}", msg),
+            ClientError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(err: io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
+impl From<ureq::Error> for ClientError {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::Status(code, resp) => ClientError::Status(code, resp.get_url().to_string()),
+            ureq::Error::Transport(t) => ClientError::Transport(t.to_string()),
+        }
+    }
+}
+
+/// Talks to the Advent of Code website (or anything answering on the same routes).
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    data_dir: PathBuf,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: Option<String>, data_dir: &Path) -> Self {
+        Self {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            session,
+            data_dir: data_dir.to_path_buf(),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| String::from(DEFAULT_BASE_URL));
+        let session = env::var(SESSION_VAR).ok().filter(|s| !s.is_empty());
+        return Self::new(&base_url, session, Path::new(DATA_DIR));
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Where the input of a given day is stored, e.g. `data/input05.txt`
+    pub fn input_path(&self, day: u32) -> PathBuf {
+        return self.data_dir.join(input::file_name(day));
+    }
+
+    fn day_url(&self, day: u32, route: &str) -> Result<String, ClientError> {
+        if !(1..=25).contains(&day) {
+            return Err(ClientError::InvalidDay(day));
+        }
+        return Ok(format!("{}/day/{}{}", self.base_url, day, route));
+    }
+
+    fn cookie(&self) -> Result<String, ClientError> {
+        let session = self.session.as_ref().ok_or(ClientError::MissingSession)?;
+        return Ok(format!("session={}", session));
+    }
+
+    /// Get the input of the day, downloading it only if it is not already in the data folder.
+    /// Empty files (as created by `newday.sh`) do not count as cached.
+    pub fn fetch_input(&self, day: u32) -> Result<PathBuf, ClientError> {
+        let url = self.day_url(day, "/input")?;
+        let path = self.input_path(day);
+        if fs::metadata(&path).map(|m| m.len() > 0).unwrap_or(false) {
+            return Ok(path);
+        }
+
+        let contents = self.agent.get(&url)
+            .set("Cookie", &self.cookie()?)
+            .call()?
+            .into_string()?;
+
+        // Write to a temporary file first so that an interrupted download is not mistaken for a cached input
+        fs::create_dir_all(&self.data_dir)?;
+        let tmp_path = path.with_extension("part");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(&tmp_path, &path)?;
+        return Ok(path);
+    }
+
+    /// Post an answer for a part of a day, returning the page sent back by the server
+    pub fn post_answer(&self, day: u32, part: u32, answer: &str) -> Result<String, ClientError> {
+        let url = self.day_url(day, "/answer")?;
+        let page = self.agent.post(&url)
+            .set("Cookie", &self.cookie()?)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?
+            .into_string()?;
+        return Ok(page);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{offline_client, serve_once, temp_dir, Received};
+
+    #[test]
+    fn day_urls() {
+        let client = Client::new("http://localhost:8000/2022/", None, Path::new(DATA_DIR));
+        assert_eq!(client.day_url(5, "/input").unwrap(), "http://localhost:8000/2022/day/5/input");
+        assert_eq!(client.day_url(25, "/answer").unwrap(), "http://localhost:8000/2022/day/25/answer");
+        assert!(matches!(client.day_url(0, "/input"), Err(ClientError::InvalidDay(0))));
+        assert!(matches!(client.day_url(26, "/input"), Err(ClientError::InvalidDay(26))));
+        assert!(matches!(client.fetch_input(26), Err(ClientError::InvalidDay(26))));
+    }
+
+    #[test]
+    fn cached_input_not_downloaded_again() {
+        let dir = temp_dir("aoc-cached");
+        // Without a session either, any download would fail
+        let client = offline_client(None, &dir);
+        fs::write(client.input_path(3), "vJrwpWtwJgWrhcsFMMfFFhFp\n").unwrap();
+        assert_eq!(client.fetch_input(3).unwrap(), dir.join("input03.txt"));
+        assert_eq!(fs::read_to_string(dir.join("input03.txt")).unwrap(), "vJrwpWtwJgWrhcsFMMfFFhFp\n");
+
+        // An empty file, as created by newday.sh, is downloaded
+        fs::write(client.input_path(4), "").unwrap();
+        assert!(matches!(client.fetch_input(4), Err(ClientError::MissingSession)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn input_downloaded_from_base_url() {
+        let (base_url, website) = serve_once("mjqjpqmgbljsphdztnvjfqwrcgsmlb\n");
+        let dir = temp_dir("aoc-download");
+        let client = Client::new(&base_url, Some(String::from("abc")), &dir);
+        let path = client.fetch_input(6).unwrap();
+        assert_eq!(website.join().unwrap(), Received {
+            url: String::from("/2022/day/6/input"),
+            cookie: Some(String::from("session=abc")),
+            body: String::new(),
+        });
+        assert_eq!(fs::read_to_string(&path).unwrap(), "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n");
+        // The server is gone, so this only works from the cache
+        assert_eq!(client.fetch_input(6).unwrap(), path);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::panic::{self, UnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use tiny_http::{Header, Response, Server};
+
+use crate::aoc::DATA_DIR;
+use crate::input::Input;
+use crate::solutions;
+use crate::submit::{History, Verdict};
+
+const LAST_DAY: u32 = 25;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }
+pre { background: #0f0f23; color: #ccc; padding: 1em; overflow-x: auto; line-height: 1.1; }
+.pass { color: #090; } .fail { color: #c00; } .unknown { color: #888; }";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Fail,
+    /// No known answer to compare to
+    Unknown,
+    Panicked,
+}
+
+impl Status {
+    fn class_and_label(self) -> (&'static str, &'static str) {
+        match self {
+            Status::Pass     => ("pass", "pass"),
+            Status::Fail     => ("fail", "fail"),
+            Status::Unknown  => ("unknown", "unknown"),
+            Status::Panicked => ("fail", "panicked"),
+        }
+    }
+}
+
+struct Outcome {
+    answer: Option<String>,
+    time: Duration,
+    status: Status,
+}
+
+/// Run a function, catching panics (e.g. due to a missing input) so that the server keeps going
+fn timed_run<F: FnOnce() -> String + UnwindSafe>(f: F) -> (Option<String>, Duration) {
+    let start = Instant::now();
+    let result = panic::catch_unwind(f).ok();
+    return (result, start.elapsed());
+}
+
+fn escape_html(s: &str) -> String {
+    return s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+}
+
+/// Answers and drawings are computed the first time they are asked for, then kept.
+struct Dashboard {
+    history: History,
+    outcomes: HashMap<(u32, u32), Outcome>,
+    renders: HashMap<u32, Vec<(&'static str, Option<String>)>>,
+}
+
+impl Dashboard {
+    fn new(history: History) -> Self {
+        Self { history, outcomes: HashMap::new(), renders: HashMap::new() }
+    }
+
+    fn status(&self, day: u32, part: u32, answer: Option<&str>) -> Status {
+        let answer = match answer {
+            None => return Status::Panicked,
+            Some(answer) => answer.trim(),
+        };
+        // Drawings cannot be compared to the letters which were submitted
+        if answer.contains('\n') {
+            return Status::Unknown;
+        }
+        return match self.history.known_verdict(day, part, answer) {
+            Some(Verdict::Correct) => Status::Pass,
+            Some(Verdict::RateLimited) | None => Status::Unknown,
+            Some(_) => Status::Fail,
+        };
+    }
+
+    fn outcome(&mut self, day: u32, part: u32) -> Option<&Outcome> {
+        let solver = solutions::solver(day, part)?;
+        if !self.outcomes.contains_key(&(day, part)) {
+            let (answer, time) = timed_run(|| solver(&Input::read_day(day)));
+            let status = self.status(day, part, answer.as_deref());
+            self.outcomes.insert((day, part), Outcome { answer, time, status });
+        }
+        return self.outcomes.get(&(day, part));
+    }
+
+    fn renders(&mut self, day: u32) -> &Vec<(&'static str, Option<String>)> {
+        return self.renders.entry(day).or_insert_with(|| {
+            solutions::renders(day).into_iter()
+                // Drawn from the same input as the answers
+                .map(|(title, render)| (title, timed_run(|| render(&Input::read_day(day))).0))
+                .collect()
+        });
+    }
+
+    fn outcome_cells(&mut self, day: u32, part: u32) -> String {
+        return match self.outcome(day, part) {
+            None => String::from("<td colspan=\"3\" class=\"unknown\">not solved</td>"),
+            Some(outcome) => {
+                let (class, label) = outcome.status.class_and_label();
+                let answer = match &outcome.answer {
+                    Some(a) if a.trim().contains('\n') => String::from("<i>drawing</i>"),
+                    Some(a) => format!("<code>{}</code>", escape_html(a)),
+                    None => String::new(),
+                };
+                format!("<td>{}</td><td>{:.1} ms</td><td class=\"{}\">{}</td>",
+                    answer, outcome.time.as_secs_f64() * 1000.0, class, label)
+            },
+        };
+    }
+
+    fn index_page(&mut self) -> String {
+        let mut rows = String::new();
+        for day in 1..=LAST_DAY {
+            let (part1, part2) = (self.outcome_cells(day, 1), self.outcome_cells(day, 2));
+            writeln!(rows, "<tr><td><a href=\"/day/{0}\">Day {0}</a></td>{1}{2}</tr>", day, part1, part2).unwrap();
+        }
+        return page("Advent of Code 2022", &format!(
+            "<table>\n<tr><th></th><th colspan=\"3\">Part 1</th><th colspan=\"3\">Part 2</th></tr>\n\
+            <tr><th>Day</th><th>Answer</th><th>Time</th><th>Status</th><th>Answer</th><th>Time</th><th>Status</th></tr>\n\
+            {}</table>", rows));
+    }
+
+    fn day_page(&mut self, day: u32) -> String {
+        let mut body = String::from("<p><a href=\"/\">Back to all days</a></p>\n");
+        for part in 1..=2 {
+            writeln!(body, "<h2>Part {}</h2>", part).unwrap();
+            let outcome = match self.outcome(day, part) {
+                None => {
+                    body.push_str("<p class=\"unknown\">Not solved.</p>\n");
+                    continue;
+                },
+                Some(outcome) => outcome,
+            };
+            let (class, label) = outcome.status.class_and_label();
+            writeln!(body, "<p class=\"{}\">{} in {:.1} ms</p>", class, label, outcome.time.as_secs_f64() * 1000.0).unwrap();
+            if let Some(answer) = &outcome.answer {
+                writeln!(body, "<pre>{}</pre>", escape_html(answer.trim_matches('\n'))).unwrap();
+            }
+        }
+        for (title, drawing) in self.renders(day) {
+            writeln!(body, "<h2>{}</h2>", title).unwrap();
+            match drawing {
+                Some(drawing) => writeln!(body, "<pre>{}</pre>", escape_html(drawing.trim_matches('\n'))).unwrap(),
+                None => body.push_str("<p class=\"fail\">Could not draw it.</p>\n"),
+            }
+        }
+        return page(&format!("Day {}", day), &body);
+    }
+}
+
+fn page(title: &str, body: &str) -> String {
+    return format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title><style>{1}</style></head>\n\
+        <body>\n<h1>{0}</h1>\n{2}\n</body>\n</html>\n",
+        title, STYLE, body);
+}
+
+/// Serve the dashboard on localhost until the process is stopped.
+pub fn serve(port: u16) -> Result<(), String> {
+    let history = History::load(Path::new(DATA_DIR)).map_err(|err| err.to_string())?;
+    let mut dashboard = Dashboard::new(history);
+
+    let server = Server::http(("127.0.0.1", port)).map_err(|err| err.to_string())?;
+    println!("Dashboard available at http://127.0.0.1:{}/", port);
+
+    let html_header = Header::from_bytes("Content-Type", "text/html; charset=utf-8").unwrap();
+    for request in server.incoming_requests() {
+        let day = request.url().strip_prefix("/day/")
+            .and_then(|d| d.parse::<u32>().ok())
+            .filter(|d| (1..=LAST_DAY).contains(d));
+        let (code, body) = match (request.url(), day) {
+            ("/", _) => (200, dashboard.index_page()),
+            (_, Some(day)) => (200, dashboard.day_page(day)),
+            _ => (404, page("Not found", "<p><a href=\"/\">Back to all days</a></p>")),
+        };
+        let response = Response::from_string(body)
+            .with_status_code(code)
+            .with_header(html_header.clone());
+        if let Err(err) = request.respond(response) {
+            eprintln!("Could not answer: {}", err);
+        }
+    }
+    return Ok(());
+}
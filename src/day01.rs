@@ -1,63 +1,134 @@
-use std::fs;
-use std::cmp::max;
+use crate::input::{Input, ReaderLines};
+use crate::top_k::{top_k, TopK};
+use std::cmp::Reverse;
+use std::fmt;
+use std::io::{self, BufRead};
 
-pub fn main01_1() -> i32 {
-    let contents = fs::read_to_string("data/01.txt").expect("Should have been able to read the file.");
+const INPUT_FILE: &str = "data/input01.txt";
 
-    let max_total = contents
-        .split("\n\n")
-        .map(|s| 
+/// Total calories carried by each elf, in the order of the input
+pub fn elf_totals(contents: &Input) -> Vec<u64> {
+    return contents
+        .blocks()
+        .map(|s|
             s.lines()
-            .map(|x| x.parse::<i32>().unwrap())
+            .map(|x| x.parse::<u64>().unwrap())
             .sum()
         )
-        .max().unwrap();
-
-    // let mut loc_total = 0;
-    // let mut max_total = 0;
-    // for line in contents.lines() {
-    //     let loc: i32 = line.parse().unwrap_or(0);
-    //     loc_total += loc;
-    //     if loc == 0 {
-    //         max_total = max(max_total, loc_total);
-    //         loc_total = 0;
-    //     }
-    // }
-    return max_total;
-}
-
-
-fn insert01_2(max_total : &mut [i64; 3], loc_total : i64) {
-    if loc_total > max_total[2] {
-        if loc_total > max_total[1] {
-            if loc_total > max_total[0] {
-                max_total[2] = max_total[1];
-                max_total[1] = max_total[0];
-                max_total[0] = max(max_total[0], loc_total);
-            }
-            else {
-                max_total[2] = max_total[1];
-                max_total[1] = max(max_total[1], loc_total);
+        .collect();
+}
+
+/// Total calories carried by each elf, reading one line at a time
+pub struct ElfTotals<R: BufRead> {
+    lines: ReaderLines<R>,
+}
+
+impl<R: BufRead> ElfTotals<R> {
+    pub fn new(reader: R) -> Self {
+        Self { lines: ReaderLines::new(reader) }
+    }
+}
+
+impl<R: BufRead> Iterator for ElfTotals<R> {
+    type Item = io::Result<u64>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut total = None;
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err)),
+            };
+            if line.is_empty() {
+                // Several blank lines in a row do not make empty elves
+                if total.is_some() { break; }
+            } else {
+                *total.get_or_insert(0) += line.parse::<u64>().unwrap();
             }
         }
-        else {
-            max_total[2] = max(max_total[2], loc_total);
-        }
+        return total.map(Ok);
     }
 }
 
-pub fn main01_2() -> i64 {
-    let contents = fs::read_to_string("data/01.txt").expect("Should have been able to read the file.");
-    let mut loc_total = 0;
-    let mut max_total = [0, 0, 0];
-    for line in contents.lines() {
-        let loc : i64 = line.parse().unwrap_or(0);
-        loc_total += loc;
-        if loc == 0 {
-            insert01_2(&mut max_total, loc_total);
-            loc_total = 0;
+/// Sum of the `k` largest totals, in constant memory
+fn sum_top_totals<R: BufRead>(reader: R, k: usize) -> io::Result<u64> {
+    let mut top = TopK::new(k);
+    for total in ElfTotals::new(reader) {
+        top.push(total?);
+    }
+    return Ok(top.into_sorted_vec().into_iter().sum());
+}
+
+/// Elves carrying the most calories, as (index, total) from the largest total.
+/// On equal totals, the first elf of the input comes first.
+pub fn top_elves(totals: &[u64], k: usize) -> Vec<(usize, u64)> {
+    let ranked = totals.iter().enumerate().map(|(idx, &total)| (total, Reverse(idx)));
+    return top_k(ranked, k).into_iter()
+        .map(|(total, Reverse(idx))| (idx, total))
+        .collect();
+}
+
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub number_of_elves: usize,
+    pub mean: f64,
+    pub median: f64,
+    /// (index, total) of the elves carrying the most
+    pub top: Vec<(usize, u64)>,
+}
+
+impl Report {
+    pub fn new(totals: &[u64], k: usize) -> Self {
+        let n = totals.len();
+        let mean = totals.iter().sum::<u64>() as f64 / n as f64;
+
+        let mut sorted = totals.to_vec();
+        sorted.sort_unstable();
+        let median = match n {
+            0 => f64::NAN,
+            n if n % 2 == 1 => sorted[n / 2] as f64,
+            n => (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0,
+        };
+
+        return Self { number_of_elves: n, mean, median, top: top_elves(totals, k) };
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} elves, carrying {:.1} calories on average (median {:.1})",
+            self.number_of_elves, self.mean, self.median)?;
+        for (rank, (idx, total)) in self.top.iter().enumerate() {
+            writeln!(f, "#{:<3} elf {:<5} {} calories", rank + 1, idx, total)?;
         }
+        return Ok(());
     }
-    insert01_2(&mut max_total, loc_total);
-    return max_total.into_iter().sum();
+}
+
+pub fn main01_1() -> u64 {
+    return solve(&Input::read(INPUT_FILE));
+}
+
+pub fn solve(contents: &Input) -> u64 {
+    return top_k(elf_totals(contents), 1).into_iter().sum();
+}
+
+pub fn solve_reader<R: BufRead>(reader: R) -> io::Result<u64> {
+    return sum_top_totals(reader, 1);
+}
+
+pub fn main01_2() -> u64 {
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> u64 {
+    return top_k(elf_totals(contents), 3).into_iter().sum();
+}
+
+pub fn solve_bonus_reader<R: BufRead>(reader: R) -> io::Result<u64> {
+    return sum_top_totals(reader, 3);
+}
+
+/// Statistics on the calories, with the `k` elves carrying the most
+pub fn main_report(k: usize) -> Report {
+    return Report::new(&elf_totals(&Input::read(INPUT_FILE)), k);
 }
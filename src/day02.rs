@@ -1,97 +1,356 @@
+use crate::input::{sum_over_lines, Input};
+use std::collections::HashMap;
 use std::fs;
+use std::io::{self, BufRead};
 
-#[derive(PartialEq)]
-enum Shape{
-    Rock, 
-    Paper, 
-    Scissors,
+const INPUT_FILE: &str = "data/input02.txt";
+
+/// Rules of the strategy guide of the puzzle
+const RPS_SPEC: &str = include_str!("../data/game02_rps.txt");
+
+/// Index of a shape in the cycle of its game
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Shape(usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
 }
 
-fn shape_from_char(s: &char) -> Result<Shape,&str> {
-    match s {
-        'A' | 'X' => Ok(Shape::Rock),
-        'B' | 'Y' => Ok(Shape::Paper),
-        'C' | 'Z' => Ok(Shape::Scissors),
-        _ => Err("Shape unknown!")
-    }
+/// A cyclic game with an odd number n of shapes, where each shape beats the (n-1)/2 shapes
+/// before it in the cycle and loses against the (n-1)/2 after it.
+///
+/// The spec is made of `key: values` lines, `#` starting a comment:
+///
+/// ```text
+/// shapes: Rock Paper Scissors
+/// shape scores: 1 2 3
+/// outcome scores: 0 3 6        # loss, draw, win
+/// opponent letters: A B C
+/// player letters: X Y Z
+/// strategy letters: X Y Z      # asking to lose, draw, win
+/// ```
+#[derive(Clone, Debug)]
+pub struct Game {
+    shapes: Vec<String>,
+    shape_scores: Vec<usize>,
+    outcome_scores: [usize; 3],
+    opponent_letters: HashMap<char, Shape>,
+    player_letters: HashMap<char, Shape>,
+    strategy_letters: HashMap<char, Outcome>,
 }
 
-fn win_score(opponent: &Shape, player: &Shape) -> usize {
-    match (opponent, player) {
-        (Shape::Rock,     Shape::Paper) => 6,
-        (Shape::Paper,    Shape::Scissors) => 6,
-        (Shape::Scissors, Shape::Rock) => 6,
-        // (so, sp) => 3,
-        (so,sp) => if so == sp {3} else {0}
-    }
+fn parse_scores(values: &str) -> Result<Vec<usize>, String> {
+    return values.split_whitespace()
+        .map(|v| v.parse::<usize>().map_err(|_| format!("Invalid score '{}'", v)))
+        .collect();
 }
 
-fn shape_score(s: &Shape) -> usize {
-    match s {
-        Shape::Rock     => 1,
-        Shape::Paper    => 2,
-        Shape::Scissors => 3,
+fn parse_letters<T: Copy>(values: &str, targets: &[T]) -> Result<HashMap<char, T>, String> {
+    let letters = values.split_whitespace()
+        .map(|v| {
+            let mut chars = v.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(format!("'{}' is not a single letter", v)),
+            }
+        })
+        .collect::<Result<Vec<char>, String>>()?;
+    if letters.len() != targets.len() {
+        return Err(format!("Expected {} letters, got {}", targets.len(), letters.len()));
+    }
+    let mapping: HashMap<char, T> = letters.iter().copied().zip(targets.iter().copied()).collect();
+    if mapping.len() != letters.len() {
+        return Err(format!("Letters '{}' are not all different", values.trim()));
     }
+    return Ok(mapping);
 }
 
-fn parse_round(line: &str) -> (Shape, Shape) {
-    let opponent = shape_from_char(&line.chars().nth(0).unwrap()).unwrap();
-    let player = shape_from_char(&line.chars().nth(2).unwrap()).unwrap();
-    return (opponent, player);
-}
+impl Game {
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let mut fields = HashMap::new();
+        for line in spec.lines() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, values) = line.split_once(':')
+                .ok_or_else(|| format!("Expected 'key: values', got '{}'", line))?;
+            fields.insert(key.trim(), values);
+        }
+        let field = |key: &str| fields.get(key).copied().ok_or_else(|| format!("Missing '{}'", key));
 
-pub fn main() -> usize {
-    let contents = fs::read_to_string("data/input02.txt").expect("Should have been able to read the file.");
-    let mut total_score = 0;
-    for line in contents.lines() {
-        let (opponent, player) = parse_round(&line);
-        total_score += win_score(&opponent, &player);
-        total_score += shape_score(&player);
+        let shapes: Vec<String> = field("shapes")?.split_whitespace().map(String::from).collect();
+        let n = shapes.len();
+        // With a single shape, every round would be a draw
+        if n < 3 || n.is_multiple_of(2) {
+            return Err(format!("A cyclic game needs an odd number of shapes, at least 3, got {}", n));
+        }
+        let shape_scores = parse_scores(field("shape scores")?)?;
+        if shape_scores.len() != n {
+            return Err(format!("Expected {} shape scores, got {}", n, shape_scores.len()));
+        }
+        let outcome_scores: [usize; 3] = parse_scores(field("outcome scores")?)?
+            .try_into()
+            .map_err(|_| String::from("Expected 3 outcome scores (loss, draw, win)"))?;
+
+        let all_shapes: Vec<Shape> = (0..n).map(Shape).collect();
+        let outcomes = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+        return Ok(Self {
+            shapes,
+            shape_scores,
+            outcome_scores,
+            opponent_letters: parse_letters(field("opponent letters")?, &all_shapes)?,
+            player_letters: parse_letters(field("player letters")?, &all_shapes)?,
+            strategy_letters: parse_letters(field("strategy letters")?, &outcomes)?,
+        });
     }
-    return total_score;
-}
 
+    pub fn read(path: &str) -> Result<Self, String> {
+        let spec = fs::read_to_string(path).map_err(|err| format!("Cannot read {}: {}", path, err))?;
+        return Self::from_spec(&spec);
+    }
 
-fn loses_against(opponent: &Shape) -> Shape {
-    match opponent {
-        Shape::Rock     => Shape::Paper,
-        Shape::Paper    => Shape::Scissors,
-        Shape::Scissors => Shape::Rock,
+    /// The game of the puzzle
+    pub fn rock_paper_scissors() -> Self {
+        return Self::from_spec(RPS_SPEC).unwrap();
     }
-}
 
-fn draws_against(opponent: &Shape) -> Shape {
-    match opponent {
-        Shape::Rock     => Shape::Rock,
-        Shape::Paper    => Shape::Paper,
-        Shape::Scissors => Shape::Scissors,
+    pub fn shape_name(&self, s: Shape) -> &str {
+        return &self.shapes[s.0];
+    }
+
+    pub fn outcome(&self, opponent: Shape, player: Shape) -> Outcome {
+        let n = self.shapes.len();
+        return match (player.0 + n - opponent.0) % n {
+            0 => Outcome::Draw,
+            d if d <= n / 2 => Outcome::Win,
+            _ => Outcome::Loss,
+        };
+    }
+
+    pub fn all_shapes(&self) -> impl Iterator<Item = Shape> + Clone {
+        return (0..self.shapes.len()).map(Shape);
+    }
+
+    /// The closest shape in the cycle giving that outcome
+    pub fn shape_for(&self, opponent: Shape, outcome: Outcome) -> Shape {
+        let n = self.shapes.len();
+        return match outcome {
+            Outcome::Loss => Shape((opponent.0 + n - 1) % n),
+            Outcome::Draw => opponent,
+            Outcome::Win  => Shape((opponent.0 + 1) % n),
+        };
+    }
+
+    /// The best-scoring shape giving that outcome. With more than three shapes, it may not be
+    /// the one of `shape_for`.
+    pub fn best_shape_for(&self, opponent: Shape, outcome: Outcome) -> Shape {
+        return self.all_shapes()
+            .filter(|&player| self.outcome(opponent, player) == outcome)
+            .max_by_key(|&player| self.score(opponent, player))
+            .unwrap();
+    }
+
+    pub fn win_score(&self, opponent: Shape, player: Shape) -> usize {
+        return self.outcome_scores[self.outcome(opponent, player) as usize];
+    }
+
+    pub fn shape_score(&self, s: Shape) -> usize {
+        return self.shape_scores[s.0];
+    }
+
+    pub fn score(&self, opponent: Shape, player: Shape) -> usize {
+        return self.win_score(opponent, player) + self.shape_score(player);
+    }
+
+    fn player_letter(&self, s: Shape) -> char {
+        return *self.player_letters.iter().find(|(_, &shape)| shape == s).unwrap().0;
+    }
+
+    fn letters<'a>(&self, line: &'a str) -> (&'a str, &'a str) {
+        let mut columns = line.split_whitespace();
+        let opponent = columns.next().unwrap();
+        let player = columns.next().unwrap();
+        return (opponent, player);
+    }
+
+    fn lookup<T: Copy>(mapping: &HashMap<char, T>, letter: &str) -> T {
+        let c = letter.chars().next().unwrap();
+        return *mapping.get(&c).unwrap_or_else(|| panic!("Unknown letter '{}'", c));
+    }
+
+    fn parse_round(&self, line: &str) -> (Shape, Shape) {
+        let (opponent, player) = self.letters(line);
+        return (Self::lookup(&self.opponent_letters, opponent), Self::lookup(&self.player_letters, player));
+    }
+
+    fn parse_round_bonus(&self, line: &str) -> (Shape, Outcome) {
+        let (opponent, strategy) = self.letters(line);
+        return (Self::lookup(&self.opponent_letters, opponent), Self::lookup(&self.strategy_letters, strategy));
+    }
+
+    /// Score of a round where the second column is the shape to play
+    pub fn round_score(&self, line: &str) -> usize {
+        let (opponent, player) = self.parse_round(line);
+        return self.score(opponent, player);
+    }
+
+    /// Score of a round where the second column is the outcome to reach
+    pub fn round_score_bonus(&self, line: &str) -> usize {
+        let (opponent, outcome) = self.parse_round_bonus(line);
+        return self.score(opponent, self.shape_for(opponent, outcome));
     }
-}
 
-fn wins_against(opponent: &Shape) -> Shape {
-    match opponent {
-        Shape::Rock     => Shape::Scissors,
-        Shape::Paper    => Shape::Rock,
-        Shape::Scissors => Shape::Paper,
+    /// Lowest and highest scores of a round, whatever the second column says
+    pub fn round_score_range(&self, line: &str) -> (usize, usize) {
+        let (opponent, _) = self.letters(line);
+        let opponent = Self::lookup(&self.opponent_letters, opponent);
+        let scores = self.all_shapes().map(|player| self.score(opponent, player));
+        return (scores.clone().min().unwrap(), scores.max().unwrap());
     }
 }
 
+pub fn main() -> usize {
+    return solve(&Input::read(INPUT_FILE));
+}
+
+pub fn solve(contents: &Input) -> usize {
+    return solve_with(&Game::rock_paper_scissors(), contents);
+}
+
+pub fn solve_with(game: &Game, contents: &Input) -> usize {
+    return contents.lines().map(|l| game.round_score(l)).sum();
+}
+
+pub fn solve_reader<R: BufRead>(reader: R) -> io::Result<usize> {
+    let game = Game::rock_paper_scissors();
+    return sum_over_lines(reader, |l| game.round_score(l));
+}
+
 pub fn main_bonus() -> usize {
-    let contents = fs::read_to_string("data/input02.txt").expect("Should have been able to read the file.");
-    let mut total_score = 0;
-    for line in contents.lines() {
-        let opponent = shape_from_char(&line.chars().nth(0).unwrap()).unwrap();
-
-        let strategy = match line.chars().nth(2).unwrap() {
-            'X' => wins_against,  // so we lose
-            'Y' => draws_against, // so we draw
-            'Z' => loses_against,  // so we win
-            _   => loses_against
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> usize {
+    return solve_bonus_with(&Game::rock_paper_scissors(), contents);
+}
+
+pub fn solve_bonus_with(game: &Game, contents: &Input) -> usize {
+    return contents.lines().map(|l| game.round_score_bonus(l)).sum();
+}
+
+pub fn solve_bonus_reader<R: BufRead>(reader: R) -> io::Result<usize> {
+    let game = Game::rock_paper_scissors();
+    return sum_over_lines(reader, |l| game.round_score_bonus(l));
+}
+
+/// Lowest and highest total scores which can be reached against the opponent column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScoreRange {
+    pub worst: usize,
+    pub best: usize,
+}
+
+pub fn score_range(game: &Game, contents: &Input) -> ScoreRange {
+    return contents.lines()
+        .map(|l| game.round_score_range(l))
+        .fold(ScoreRange { worst: 0, best: 0 }, |range, (worst, best)| {
+            ScoreRange { worst: range.worst + worst, best: range.best + best }
+        });
+}
+
+/// Rewrite a guide whose second column is the outcome to reach into one whose second column
+/// is the best-scoring shape reaching it. Part 1 scores it as part 2 does for rock paper
+/// scissors, and possibly higher for larger games.
+pub fn strategy_guide(game: &Game, contents: &Input) -> String {
+    return contents.lines()
+        .map(|l| {
+            let (opponent, outcome) = game.parse_round_bonus(l);
+            let (opponent_letter, _) = game.letters(l);
+            format!("{} {}\n", opponent_letter, game.player_letter(game.best_shape_for(opponent, outcome)))
+        })
+        .collect();
+}
+
+pub fn main_score_range() -> ScoreRange {
+    return score_range(&Game::rock_paper_scissors(), &Input::read(INPUT_FILE));
+}
+
+pub fn main_strategy_guide() -> String {
+    return strategy_guide(&Game::rock_paper_scissors(), &Input::read(INPUT_FILE));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rpsls() -> Game {
+        return Game::read("data/game02_rpsls.txt").unwrap();
+    }
+
+    fn shape(game: &Game, name: &str) -> Shape {
+        return game.all_shapes().find(|&s| game.shape_name(s) == name).unwrap();
+    }
+
+    #[test]
+    fn closest_and_best_shapes_for_an_outcome() {
+        let game = rpsls();
+        let names = |opponent: &str, outcome: Outcome| {
+            let opponent = shape(&game, opponent);
+            let closest = game.shape_for(opponent, outcome);
+            let best = game.best_shape_for(opponent, outcome);
+            return (game.shape_name(closest).to_string(), game.shape_name(best).to_string());
         };
-        let player = strategy(&opponent);
+        let pair = |closest: &str, best: &str| (String::from(closest), String::from(best));
+
+        // Spock and paper both beat rock, paper scores more
+        assert_eq!(names("Rock", Outcome::Win), pair("Spock", "Paper"));
+        // Scissors and lizard both lose to rock, scissors score more
+        assert_eq!(names("Rock", Outcome::Loss), pair("Scissors", "Scissors"));
+        assert_eq!(names("Rock", Outcome::Draw), pair("Rock", "Rock"));
+        assert_eq!(names("Scissors", Outcome::Win), pair("Rock", "Spock"));
+        assert_eq!(names("Scissors", Outcome::Loss), pair("Lizard", "Lizard"));
+    }
+
+    #[test]
+    fn part_two_plays_the_closest_shape() {
+        let game = rpsls();
+        // Spock against rock: 6 + 2, where paper would give 6 + 3
+        assert_eq!(game.round_score_bonus("A Z"), 8);
+        assert_eq!(game.round_score_bonus("A X"), 5);
+        assert_eq!(game.round_score_bonus("E Z"), 7);
+    }
 
-        total_score += win_score(&opponent, &player);
-        total_score += shape_score(&player);
+    #[test]
+    fn strategy_guide_plays_the_best_shape() {
+        let game = rpsls();
+        let guide = strategy_guide(&game, &Input::normalize("A Z\nA X\nE Z\n"));
+        assert_eq!(guide, "A X\nA Z\nE W\n");
+        assert_eq!(guide.lines().map(|l| game.round_score(l)).sum::<usize>(), 9 + 5 + 8);
     }
-    return total_score;
-}
\ No newline at end of file
+
+    #[test]
+    fn number_of_shapes() {
+        let spec = |shapes: &str| format!(
+            "shapes: {}\nshape scores: 1 2 3\noutcome scores: 0 3 6\n\
+             opponent letters: A B C\nplayer letters: X Y Z\nstrategy letters: X Y Z\n", shapes);
+        assert!(Game::from_spec(&spec("Rock Paper Scissors")).is_ok());
+        let err = |shapes: &str| Game::from_spec(&spec(shapes)).err();
+        assert_eq!(err("Rock"), Some(String::from("A cyclic game needs an odd number of shapes, at least 3, got 1")));
+        assert_eq!(err(""), Some(String::from("A cyclic game needs an odd number of shapes, at least 3, got 0")));
+        assert_eq!(err("Rock Paper"), Some(String::from("A cyclic game needs an odd number of shapes, at least 3, got 2")));
+    }
+
+    #[test]
+    fn rock_paper_scissors_shapes_agree() {
+        let game = Game::rock_paper_scissors();
+        for opponent in game.all_shapes() {
+            for outcome in [Outcome::Loss, Outcome::Draw, Outcome::Win] {
+                assert_eq!(game.shape_for(opponent, outcome), game.best_shape_for(opponent, outcome));
+            }
+        }
+    }
+}
@@ -1,21 +1,38 @@
-use std::fs;
+use crate::input::{Input, ReaderLines};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead};
 
 const INPUT_FILE: &str = "data/input03.txt";
 
-fn common_item(rucksack: &str) -> char {
-    let separating_index = rucksack.len() / 2;
-
-    let compartment1 = &rucksack[..separating_index];
-    let compartment2 = &rucksack[separating_index..];
+#[derive(Debug, PartialEq, Eq)]
+pub enum RucksackError {
+    UnevenSplit { rucksack: String, compartments: usize },
+    NotAnItem(char),
+    /// The compartments or rucksacks which have nothing in common (none at all if empty)
+    NoCommonItem(Vec<String>),
+    /// Number of rucksacks in the last group, which is not full
+    IncompleteGroup(usize),
+}
 
-    // Find common item
-    for item1 in compartment1.chars() {
-        for item2 in compartment2.chars() {
-            if item1 == item2 {return item1};
-        };
-    };
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RucksackError::UnevenSplit { rucksack, compartments } =>
+                write!(f, "'{}' cannot be split into {} compartments of the same size", rucksack, compartments),
+            RucksackError::NotAnItem(c) => write!(f, "'{}' is not an item", c),
+            RucksackError::NoCommonItem(parts) if parts.is_empty() => write!(f, "no parts to find a common item in"),
+            RucksackError::NoCommonItem(parts) => write!(f, "nothing in common between {}", parts.join(", ")),
+            RucksackError::IncompleteGroup(n) => write!(f, "the last group only has {} rucksacks", n),
+        }
+    }
+}
 
-    return ' ';
+impl From<RucksackError> for io::Error {
+    fn from(err: RucksackError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+    }
 }
 
 fn item_value(item: char) -> u32 {
@@ -26,35 +43,260 @@ fn item_value(item: char) -> u32 {
     }
 }
 
-pub fn main() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-
-    return contents.lines().map(common_item).map(item_value).sum();
+fn item_from_value(value: u32) -> char {
+    return match value {
+        1..=26 => (b'a' + value as u8 - 1) as char,
+        _ => (b'A' + value as u8 - 27) as char,
+    };
 }
 
+/// Set of items, as a bit per priority (1 to 52)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemSet(u64);
 
-fn find_first_common(s1: &str, s2: &str, s3: &str) -> char {
-    for c1 in s1.chars() {
-        for c2 in s2.chars() {
-            if c1 == c2 {
-                for c3 in s3.chars() {
-                    if c1 == c3 {return c1;}
-                }
+impl ItemSet {
+    /// Every item, so that it is neutral for the intersection
+    pub const ALL: ItemSet = ItemSet(((1 << 52) - 1) << 1);
+
+    pub fn from_items(items: &str) -> Result<Self, RucksackError> {
+        let mut set = 0;
+        for item in items.chars() {
+            if !item.is_ascii_alphabetic() {
+                return Err(RucksackError::NotAnItem(item));
             }
+            set |= 1 << item_value(item);
         }
+        return Ok(ItemSet(set));
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        return ItemSet(self.0 & other.0);
+    }
+
+    /// Items found in every set
+    pub fn common<I: IntoIterator<Item = ItemSet>>(sets: I) -> ItemSet {
+        return sets.into_iter().fold(ItemSet::ALL, ItemSet::intersection);
+    }
+
+    pub fn is_empty(self) -> bool {
+        return self.0 == 0;
+    }
+
+    pub fn len(self) -> usize {
+        return self.0.count_ones() as usize;
+    }
+
+    /// Items of the set, by increasing priority
+    pub fn items(self) -> impl Iterator<Item = char> {
+        return (1..=52).filter(move |v| self.0 & (1 << v) != 0).map(item_from_value);
     }
-    return ' ';
 }
 
+/// Split a rucksack into `n` compartments of the same size
+pub fn compartments(rucksack: &str, n: usize) -> Result<Vec<&str>, RucksackError> {
+    if let Some(c) = rucksack.chars().find(|c| !c.is_ascii_alphabetic()) {
+        return Err(RucksackError::NotAnItem(c));
+    }
+    if n == 0 || !rucksack.len().is_multiple_of(n) {
+        return Err(RucksackError::UnevenSplit { rucksack: String::from(rucksack), compartments: n });
+    }
+    let size = rucksack.len() / n;
+    return Ok((0..n).map(|i| &rucksack[i * size..(i + 1) * size]).collect());
+}
+
+/// The item shared by all the parts (with the lowest priority if there are several). There is
+/// none without any part.
+pub fn common_item(parts: &[&str]) -> Result<char, RucksackError> {
+    if parts.is_empty() {
+        return Err(RucksackError::NoCommonItem(Vec::new()));
+    }
+    let sets = parts.iter().map(|p| ItemSet::from_items(p)).collect::<Result<Vec<_>, _>>()?;
+    return ItemSet::common(sets).items().next()
+        .ok_or_else(|| RucksackError::NoCommonItem(parts.iter().map(|p| String::from(*p)).collect()));
+}
+
+/// Priority of the item found in every compartment of a rucksack
+pub fn rucksack_priority(rucksack: &str, n_compartments: usize) -> Result<u32, RucksackError> {
+    return Ok(item_value(common_item(&compartments(rucksack, n_compartments)?)?));
+}
+
+pub fn main() -> u32 {
+    return solve(&Input::read(INPUT_FILE));
+}
+
+pub fn sum_of_priorities(contents: &Input, n_compartments: usize) -> Result<u32, RucksackError> {
+    return contents.lines().map(|l| rucksack_priority(l, n_compartments)).sum();
+}
+
+pub fn solve(contents: &Input) -> u32 {
+    return sum_of_priorities(contents, 2).unwrap_or_else(|err| panic!("{}", err));
+}
+
+pub fn solve_reader<R: BufRead>(reader: R) -> io::Result<u32> {
+    let mut total = 0;
+    for line in ReaderLines::new(reader) {
+        let line = line?;
+        if line.is_empty() { continue; }
+        total += rucksack_priority(&line, 2)?;
+    }
+    return Ok(total);
+}
+
+
 pub fn main_bonus() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
 
+/// Sum of the priorities of the badge, i.e. the item carried by every elf, of each group
+pub fn sum_of_badge_priorities(contents: &Input, group_size: usize) -> Result<u32, RucksackError> {
+    assert!(group_size > 0, "groups need at least one rucksack");
     let contents: Vec<&str> = contents.lines().collect();
 
-    return contents.chunks_exact(3)
-        .map(|s| find_first_common(s[0],s[1],s[2]))
-        .map(item_value)
+    return contents.chunks(group_size)
+        .map(|group| {
+            if group.len() < group_size {
+                return Err(RucksackError::IncompleteGroup(group.len()));
+            }
+            return Ok(item_value(common_item(group)?));
+        })
         .sum();
-}
\ No newline at end of file
+}
+
+pub fn solve_bonus(contents: &Input) -> u32 {
+    return sum_of_badge_priorities(contents, 3).unwrap_or_else(|err| panic!("{}", err));
+}
+
+pub fn solve_bonus_reader<R: BufRead>(reader: R) -> io::Result<u32> {
+    let mut total = 0;
+    let mut group: Vec<String> = Vec::with_capacity(3);
+    for line in ReaderLines::new(reader) {
+        let line = line?;
+        if line.is_empty() { continue; }
+        group.push(line);
+        if group.len() == 3 {
+            let group_refs: Vec<&str> = group.iter().map(String::as_str).collect();
+            total += item_value(common_item(&group_refs)?);
+            group.clear();
+        }
+    }
+    if !group.is_empty() {
+        return Err(RucksackError::IncompleteGroup(group.len()).into());
+    }
+    return Ok(total);
+}
+
+/// An item found in every part, with its number of copies in all of them
+#[derive(Clone, Debug, Serialize)]
+pub struct SharedItem {
+    pub item: char,
+    pub priority: u32,
+    pub copies: usize,
+}
+
+fn shared_items(parts: &[&str]) -> Result<Vec<SharedItem>, RucksackError> {
+    let sets = parts.iter().map(|p| ItemSet::from_items(p)).collect::<Result<Vec<_>, _>>()?;
+    return Ok(ItemSet::common(sets).items()
+        .map(|item| SharedItem {
+            item,
+            priority: item_value(item),
+            copies: parts.iter().map(|p| p.matches(item).count()).sum(),
+        })
+        .collect());
+}
+
+/// Items shared between the compartments of a rucksack, or between the rucksacks of a group.
+/// `index` starts at 1, as in the input.
+#[derive(Clone, Debug, Serialize)]
+pub struct SharedEntry {
+    pub index: usize,
+    pub shared: Vec<SharedItem>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Audit {
+    pub rucksacks: Vec<SharedEntry>,
+    pub groups: Vec<SharedEntry>,
+    /// Number of items of each priority in the whole input
+    pub histogram: BTreeMap<u32, usize>,
+}
+
+impl Audit {
+    pub fn new(contents: &Input, n_compartments: usize, group_size: usize) -> Result<Self, RucksackError> {
+        assert!(group_size > 0, "groups need at least one rucksack");
+        let lines: Vec<&str> = contents.lines().collect();
+
+        let rucksacks = lines.iter().enumerate()
+            .map(|(i, rucksack)| Ok(SharedEntry {
+                index: i + 1,
+                shared: shared_items(&compartments(rucksack, n_compartments)?)?,
+            }))
+            .collect::<Result<Vec<_>, RucksackError>>()?;
+
+        let groups = lines.chunks(group_size).enumerate()
+            .map(|(i, group)| {
+                if group.len() < group_size {
+                    return Err(RucksackError::IncompleteGroup(group.len()));
+                }
+                return Ok(SharedEntry { index: i + 1, shared: shared_items(group)? });
+            })
+            .collect::<Result<Vec<_>, RucksackError>>()?;
+
+        let mut histogram = BTreeMap::new();
+        for item in lines.iter().flat_map(|l| l.chars()) {
+            *histogram.entry(item_value(item)).or_insert(0) += 1;
+        }
+
+        return Ok(Self { rucksacks, groups, histogram });
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(self).unwrap();
+    }
+}
+
+fn write_entries(f: &mut fmt::Formatter, title: &str, entries: &[SharedEntry]) -> fmt::Result {
+    for entry in entries {
+        let shared = entry.shared.iter()
+            .map(|s| format!("{} (priority {}, {} copies)", s.item, s.priority, s.copies))
+            .collect::<Vec<_>>();
+        writeln!(f, "{} {:<4} {}", title, entry.index, shared.join(", "))?;
+    }
+    return Ok(());
+}
+
+impl fmt::Display for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_entries(f, "Rucksack", &self.rucksacks)?;
+        writeln!(f)?;
+        write_entries(f, "Group", &self.groups)?;
+        writeln!(f)?;
+
+        const BAR_WIDTH: usize = 50;
+        let largest = self.histogram.values().copied().max().unwrap_or(1);
+        writeln!(f, "Items by priority:")?;
+        for (&priority, &count) in &self.histogram {
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(largest));
+            writeln!(f, "{:>2} {} {:>5} {}", priority, item_from_value(priority), count, bar)?;
+        }
+        return Ok(());
+    }
+}
+
+/// Shared items of each rucksack and group of the input, to print or to export with `to_json`
+pub fn main_audit() -> Audit {
+    return Audit::new(&Input::read(INPUT_FILE), 2, 3).unwrap_or_else(|err| panic!("{}", err));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_items() {
+        assert_eq!(common_item(&["vJrwpWtwJgWr", "hcsFMMfFFhFp"]), Ok('p'));
+        assert_eq!(common_item(&["abc"]), Ok('a'));
+        assert_eq!(common_item(&["ab", "cd"]), Err(RucksackError::NoCommonItem(vec![String::from("ab"), String::from("cd")])));
+        assert_eq!(common_item(&[]), Err(RucksackError::NoCommonItem(Vec::new())));
+        assert_eq!(common_item(&[]).unwrap_err().to_string(), "no parts to find a common item in");
+    }
+}
@@ -1,37 +1,31 @@
-use std::fs;
-use std::cmp::{min,max};
+use crate::input::{sum_over_lines, Input};
+use crate::interval::{Interval, ParseIntervalError};
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
 
 const INPUT_FILE: &str = "data/input04.txt";
-type Range = (i32, i32);
 
-fn str_to_range(s: &str) -> Range {
-    let mut r = s.split("-");
-    let r1 = r.next().unwrap().parse().unwrap();
-    let r2 = r.next().unwrap().parse().unwrap();
-    return (r1,r2);
-}
-
-fn line_to_ranges(s: &str) -> (Range,Range) {
-    let mut ranges = s.split(",");
-    return (str_to_range(ranges.next().unwrap()), str_to_range(ranges.next().unwrap()));
+fn line_to_ranges(s: &str) -> (Interval, Interval) {
+    let mut ranges = s.split(",").map(|r| r.parse::<Interval>().unwrap_or_else(|err| panic!("{}", err)));
+    return (ranges.next().unwrap(), ranges.next().unwrap());
 }
 
 // Compares ranges r1 and r2
-fn full_overlap(r1: Range, r2: Range) -> bool {
-    let b = (r1.0 - r2.0) * (r1.1 - r2.1) <= 0;
-    return b;
+fn full_overlap(r1: Interval, r2: Interval) -> bool {
+    return r1.covers(r2) || r2.covers(r1);
 }
 
 // Compares ranges r1 and r2
-fn any_overlap(r1: Range, r2: Range) -> bool {
-    let b = max(r1.0,r2.0) <= min(r1.1,r2.1);
-    return b;
+fn any_overlap(r1: Interval, r2: Interval) -> bool {
+    return r1.overlaps(r2);
 }
 
 pub fn main() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
+pub fn solve(contents: &Input) -> u32 {
     return contents.lines()
         .map(line_to_ranges)
         .map(|r| full_overlap(r.0,r.1))
@@ -39,13 +33,327 @@ pub fn main() -> u32 {
         .sum();
 }
 
+pub fn solve_reader<R: BufRead>(reader: R) -> io::Result<u32> {
+    return sum_over_lines(reader, |line| {
+        let r = line_to_ranges(line);
+        full_overlap(r.0,r.1) as u32
+    });
+}
+
 pub fn main_bonus() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
 
+pub fn solve_bonus(contents: &Input) -> u32 {
     return contents.lines()
         .map(line_to_ranges)
         .map(|r| any_overlap(r.0,r.1))
         .map(|x| x as u32)
         .sum();
-}
\ No newline at end of file
+}
+
+pub fn solve_bonus_reader<R: BufRead>(reader: R) -> io::Result<u32> {
+    return sum_over_lines(reader, |line| {
+        let r = line_to_ranges(line);
+        any_overlap(r.0,r.1) as u32
+    });
+}
+
+pub fn parse_pairs(contents: &Input) -> Vec<(Interval, Interval)> {
+    return contents.lines().map(line_to_ranges).collect();
+}
+
+/// Number of intervals covering each section, as maximal runs of sections covered the same
+/// number of times, from the first covered section to the last one.
+/// Sorting the bounds makes it O(n log n), without comparing intervals with each other.
+pub fn coverage<I: IntoIterator<Item = Interval>>(intervals: I) -> Vec<(Interval, usize)> {
+    // +1 where an interval starts, -1 just after it ends (which may not fit in an i32)
+    let mut events: Vec<(i64, i64)> = intervals.into_iter()
+        .flat_map(|i| [(i.start() as i64, 1), (i.end() as i64 + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut runs: Vec<(Interval, usize)> = Vec::new();
+    let mut depth = 0;
+    let mut idx = 0;
+    while idx < events.len() {
+        let pos = events[idx].0;
+        while idx < events.len() && events[idx].0 == pos {
+            depth += events[idx].1;
+            idx += 1;
+        }
+        let next = match events.get(idx) {
+            Some(&(next, _)) => next,
+            None => break,
+        };
+        let run = Interval::new(pos as i32, (next - 1) as i32).unwrap();
+        match runs.last_mut() {
+            // Intervals ending where others start do not change the coverage
+            Some((last, count)) if *count == depth as usize => *last = last.union(run).unwrap(),
+            _ => runs.push((run, depth as usize)),
+        }
+    }
+    return runs;
+}
+
+/// Sections covered by the most intervals, with the number of intervals covering them
+pub fn most_covered(runs: &[(Interval, usize)]) -> (usize, Vec<Interval>) {
+    let most = runs.iter().map(|&(_, count)| count).max().unwrap_or(0);
+    return (most, runs.iter().filter(|&&(_, count)| count == most).map(|&(run, _)| run).collect());
+}
+
+/// Sections between the first and last covered ones which are not covered
+pub fn uncovered(runs: &[(Interval, usize)]) -> Vec<Interval> {
+    return runs.iter().filter(|&&(_, count)| count == 0).map(|&(run, _)| run).collect();
+}
+
+/// Largest group of pairs which all overlap with each other, i.e. whose sections assigned to
+/// both elves of the pair overlap. Such intervals all share a section, so this is the most
+/// covered section of these intersections. Pairs are given by their index in the input.
+pub fn largest_overlapping_group(pairs: &[(Interval, Interval)]) -> Vec<usize> {
+    let shared: Vec<(usize, Interval)> = pairs.iter().enumerate()
+        .filter_map(|(idx, (r1, r2))| Some((idx, r1.intersection(*r2)?)))
+        .collect();
+    let (_, sections) = most_covered(&coverage(shared.iter().map(|&(_, i)| i)));
+    return match sections.first() {
+        None => Vec::new(),
+        Some(section) => shared.iter()
+            .filter(|(_, i)| i.contains(section.start()))
+            .map(|&(idx, _)| idx)
+            .collect(),
+    };
+}
+
+#[derive(Clone, Debug)]
+pub struct SectionReport {
+    pub max_coverage: usize,
+    pub most_covered: Vec<Interval>,
+    pub uncovered: Vec<Interval>,
+    /// Indices of the pairs in the input
+    pub largest_group: Vec<usize>,
+}
+
+impl SectionReport {
+    pub fn new(pairs: &[(Interval, Interval)]) -> Self {
+        let runs = coverage(pairs.iter().flat_map(|&(r1, r2)| [r1, r2]));
+        let (max_coverage, most_covered) = most_covered(&runs);
+        return Self {
+            max_coverage,
+            most_covered,
+            uncovered: uncovered(&runs),
+            largest_group: largest_overlapping_group(pairs),
+        };
+    }
+}
+
+fn join<T: ToString>(items: &[T]) -> String {
+    if items.is_empty() {
+        return String::from("none");
+    }
+    return items.iter().map(T::to_string).collect::<Vec<_>>().join(", ");
+}
+
+impl fmt::Display for SectionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Covered by {} elves: {}", self.max_coverage, join(&self.most_covered))?;
+        writeln!(f, "Not covered: {}", join(&self.uncovered))?;
+        let lines: Vec<usize> = self.largest_group.iter().map(|idx| idx + 1).collect();
+        writeln!(f, "{} pairs overlapping with each other, on lines {}", lines.len(), join(&lines))?;
+        return Ok(());
+    }
+}
+
+pub fn main_sections() -> SectionReport {
+    return SectionReport::new(&parse_pairs(&Input::read(INPUT_FILE)));
+}
+
+/// Rectangle of sections, written `2-4x6-8`. A range without a second dimension such as `2-4`
+/// is a rectangle one section high.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: Interval,
+    pub y: Interval,
+}
+
+impl Rect {
+    pub fn covers(self, other: Rect) -> bool {
+        return self.x.covers(other.x) && self.y.covers(other.y);
+    }
+
+    pub fn overlaps(self, other: Rect) -> bool {
+        return self.x.overlaps(other.x) && self.y.overlaps(other.y);
+    }
+
+    pub fn intersection(self, other: Rect) -> Option<Rect> {
+        return Some(Rect { x: self.x.intersection(other.x)?, y: self.y.intersection(other.y)? });
+    }
+
+    pub fn area(self) -> u64 {
+        return self.x.len() * self.y.len();
+    }
+}
+
+impl FromStr for Rect {
+    type Err = ParseIntervalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.split_once('x') {
+            Some((x, y)) => Ok(Rect { x: x.parse()?, y: y.parse()? }),
+            None => Ok(Rect { x: s.parse()?, y: Interval::point(0) }),
+        };
+    }
+}
+
+fn line_to_rects(s: &str) -> (Rect, Rect) {
+    let mut rects = s.split(",").map(|r| r.parse::<Rect>().unwrap_or_else(|err| panic!("{}", err)));
+    return (rects.next().unwrap(), rects.next().unwrap());
+}
+
+/// Length covered at least once and at least twice by intervals added to a segment tree over
+/// compressed coordinates: leaf `i` stands for the sections from `bounds[i]` to `bounds[i+1]`.
+struct CoverTree {
+    bounds: Vec<i64>,
+    /// Number of intervals covering the whole node, without going down to its children
+    count: Vec<usize>,
+    once: Vec<u64>,
+    twice: Vec<u64>,
+}
+
+impl CoverTree {
+    fn new(bounds: Vec<i64>) -> Self {
+        let size = 4 * bounds.len().max(1);
+        Self { bounds, count: vec![0; size], once: vec![0; size], twice: vec![0; size] }
+    }
+
+    /// Add (or remove, with -1) the sections from `start` to `end` excluded
+    fn add(&mut self, start: i64, end: i64, delta: isize) {
+        let lo = self.bounds.binary_search(&start).unwrap();
+        let hi = self.bounds.binary_search(&end).unwrap();
+        self.update(1, 0, self.bounds.len() - 1, lo, hi, delta);
+    }
+
+    fn update(&mut self, node: usize, l: usize, r: usize, lo: usize, hi: usize, delta: isize) {
+        if hi <= l || r <= lo {
+            return;
+        }
+        if lo <= l && r <= hi {
+            self.count[node] = self.count[node].checked_add_signed(delta).unwrap();
+        } else {
+            let mid = (l + r) / 2;
+            self.update(2 * node, l, mid, lo, hi, delta);
+            self.update(2 * node + 1, mid, r, lo, hi, delta);
+        }
+        self.pull(node, l, r);
+    }
+
+    fn pull(&mut self, node: usize, l: usize, r: usize) {
+        let whole = (self.bounds[r] - self.bounds[l]) as u64;
+        let leaf = r - l == 1;
+        let children = |v: &Vec<u64>| if leaf { 0 } else { v[2 * node] + v[2 * node + 1] };
+        self.once[node] = if self.count[node] >= 1 { whole } else { children(&self.once) };
+        self.twice[node] = match self.count[node] {
+            0 => children(&self.twice),
+            1 => children(&self.once),
+            _ => whole,
+        };
+    }
+
+    fn covered_twice(&self) -> u64 {
+        return self.twice[1];
+    }
+}
+
+/// Number of sections claimed by at least two rectangles, sweeping along x over the compressed
+/// y coordinates, in O(n log n)
+pub fn claimed_more_than_once(rects: &[Rect]) -> u64 {
+    let mut ys: Vec<i64> = rects.iter()
+        .flat_map(|r| [r.y.start() as i64, r.y.end() as i64 + 1])
+        .collect();
+    ys.sort_unstable();
+    ys.dedup();
+    if ys.len() < 2 {
+        return 0;
+    }
+    let mut tree = CoverTree::new(ys);
+
+    let mut events: Vec<(i64, isize, Rect)> = rects.iter()
+        .flat_map(|&r| [(r.x.start() as i64, 1, r), (r.x.end() as i64 + 1, -1, r)])
+        .collect();
+    events.sort_unstable_by_key(|&(x, delta, _)| (x, delta));
+
+    let mut area = 0;
+    let mut last_x = events.first().map_or(0, |e| e.0);
+    for (x, delta, r) in events {
+        area += tree.covered_twice() * (x - last_x) as u64;
+        tree.add(r.y.start() as i64, r.y.end() as i64 + 1, delta);
+        last_x = x;
+    }
+    return area;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RectangleReport {
+    /// Pairs where one rectangle contains the other one
+    pub full_containment: usize,
+    pub any_overlap: usize,
+    /// Sum over the pairs of the area claimed by both rectangles
+    pub overlapping_area: u64,
+    /// Sections claimed by at least two rectangles of the whole input
+    pub claimed_more_than_once: u64,
+}
+
+impl RectangleReport {
+    pub fn new(pairs: &[(Rect, Rect)]) -> Self {
+        let rects: Vec<Rect> = pairs.iter().flat_map(|&(r1, r2)| [r1, r2]).collect();
+        return Self {
+            full_containment: pairs.iter().filter(|(r1, r2)| r1.covers(*r2) || r2.covers(*r1)).count(),
+            any_overlap: pairs.iter().filter(|(r1, r2)| r1.overlaps(*r2)).count(),
+            overlapping_area: pairs.iter().filter_map(|(r1, r2)| r1.intersection(*r2)).map(Rect::area).sum(),
+            claimed_more_than_once: claimed_more_than_once(&rects),
+        };
+    }
+}
+
+impl fmt::Display for RectangleReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Pairs where one contains the other: {}", self.full_containment)?;
+        writeln!(f, "Pairs overlapping: {}", self.any_overlap)?;
+        writeln!(f, "Area shared within pairs: {}", self.overlapping_area)?;
+        writeln!(f, "Sections claimed more than once: {}", self.claimed_more_than_once)?;
+        return Ok(());
+    }
+}
+
+pub fn parse_rect_pairs(contents: &Input) -> Vec<(Rect, Rect)> {
+    return contents.lines().map(line_to_rects).collect();
+}
+
+pub fn main_rectangles() -> RectangleReport {
+    return RectangleReport::new(&parse_rect_pairs(&Input::read(INPUT_FILE)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rects(list: &[&str]) -> Vec<Rect> {
+        return list.iter().map(|r| r.parse().unwrap()).collect();
+    }
+
+    #[test]
+    fn claimed_more_than_once_by_hand() {
+        // The fabric of 2018 day 3: only the 2 by 2 square in the middle is claimed twice
+        assert_eq!(claimed_more_than_once(&rects(&["1-4x3-6", "3-6x1-4", "5-6x5-6"])), 4);
+
+        // The squares share (2,2) (2,3) (3,2) (3,3), and the column crosses the first one from
+        // (3,0) to (3,3) and the second one from (3,2) to (3,5): 8 sections. The point is alone.
+        let claims = rects(&["0-3x0-3", "2-5x2-5", "3-3x0-9", "10-10x10-10"]);
+        assert_eq!(claimed_more_than_once(&claims), 8);
+
+        assert_eq!(claimed_more_than_once(&rects(&["-2--1x-2--1", "-1-0x-1-0"])), 1);
+        assert_eq!(claimed_more_than_once(&rects(&["0-9x0-9", "0-9x0-9"])), 100);
+        assert_eq!(claimed_more_than_once(&rects(&["0-1x0-1", "2-3x0-1"])), 0);
+        assert_eq!(claimed_more_than_once(&rects(&["0-1x0-1"])), 0);
+        assert_eq!(claimed_more_than_once(&[]), 0);
+    }
+}
@@ -1,110 +1,699 @@
-use std::fs;
+use crate::input::Input;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
 
 const INPUT_FILE: &str = "data/input05.txt";
-type Crate = char;
-type Stack = Vec<Crate>;
-type Arrangement = Vec<Stack>;
+pub type Crate = char;
+pub type Stack = Vec<Crate>;
+pub type Arrangement = Vec<Stack>;
 
+/// `move 3 from 1 to 2`, with stacks numbered from 1 as in the input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub amount: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    Syntax,
+    NoSuchStack(usize),
+    NotEnoughCrates { stack: usize, available: usize },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::Syntax => write!(f, "expected 'move N from A to B'"),
+            MoveError::NoSuchStack(stack) => write!(f, "there is no stack {}", stack),
+            MoveError::NotEnoughCrates { stack, available } =>
+                write!(f, "stack {} only has {} crates", stack, available),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum YardError {
+    MissingFooter,
+    /// A line of the drawing (numbered from 1) which cannot be read
+    InvalidDrawing { line: usize, reason: String },
+    InvalidMove { line: usize, instr: String, reason: MoveError },
+}
+
+impl fmt::Display for YardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            YardError::MissingFooter => write!(f, "the drawing has no line numbering the stacks"),
+            YardError::InvalidDrawing { line, reason } => write!(f, "line {}: {}", line, reason),
+            YardError::InvalidMove { line, instr, reason } => write!(f, "line {} '{}': {}", line, instr, reason),
+        }
+    }
+}
+
+impl From<YardError> for io::Error {
+    fn from(err: YardError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+    }
+}
 
-fn do_instr(yard: &mut Arrangement, instr: &str) {
-    let words: Vec<&str> = instr.split(" ").collect();
-    let amount: usize = words[1].parse().expect("Could not find amount.");
-    let from_idx: usize = words[3].parse().expect("Could not find 'from' stack.");
-    let to_idx: usize = words[5].parse().expect("Could not find 'to' stack.");
+impl FromStr for Move {
+    type Err = MoveError;
 
-    for _ in 0..amount {
-        let content = yard[from_idx - 1].pop().expect("Stack was empty D:");
-        yard[to_idx - 1].push(content);
+    fn from_str(instr: &str) -> Result<Self, Self::Err> {
+        let number = |s: &str| s.parse::<usize>().map_err(|_| MoveError::Syntax);
+        let words: Vec<&str> = instr.split_whitespace().collect();
+        return match words.as_slice() {
+            ["move", amount, "from", from, "to", to] => Ok(Move {
+                amount: number(amount)?, from: number(from)?, to: number(to)?,
+            }),
+            _ => Err(MoveError::Syntax),
+        };
     }
 }
 
-fn do_instr_9001(yard: &mut Arrangement, instr: &str) {
-    let words: Vec<&str> = instr.split(" ").collect();
-    let amount: usize = words[1].parse().expect("Could not find amount.");
-    let from_idx: usize = words[3].parse().expect("Could not find 'from' stack.");
-    let to_idx: usize = words[5].parse().expect("Could not find 'to' stack.");
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
+    }
+}
+
+impl Move {
+    /// The move bringing the crates back
+    pub fn reversed(self) -> Move {
+        return Move { amount: self.amount, from: self.to, to: self.from };
+    }
+
+    /// Whether the move can be done on the yard, whichever the crane
+    pub fn check(self, yard: &Arrangement) -> Result<(), MoveError> {
+        for stack in [self.from, self.to] {
+            if stack == 0 || stack > yard.len() {
+                return Err(MoveError::NoSuchStack(stack));
+            }
+        }
+        let available = yard[self.from - 1].len();
+        if available < self.amount {
+            return Err(MoveError::NotEnoughCrates { stack: self.from, available });
+        }
+        return Ok(());
+    }
+}
+
+/// Move the `n` top crates of a stack at once, keeping their order
+fn lift(yard: &mut Arrangement, from: usize, to: usize, n: usize) {
+    let stack = &mut yard[from - 1];
+    let bottom = stack.len().checked_sub(n).expect("Stack was empty D:");
+    let crates = stack.split_off(bottom);
+    yard[to - 1].extend(crates);
+}
+
+pub trait Crane {
+    /// Apply a move which has been checked against the yard
+    fn apply(&self, yard: &mut Arrangement, m: Move);
+
+    /// Cancel a move which has just been applied. Moving the crates back with the same crane
+    /// does it, for cranes which lift all the crates at once or one at a time.
+    fn undo(&self, yard: &mut Arrangement, m: Move) {
+        self.apply(yard, m.reversed());
+    }
+}
+
+/// Moves crates one at a time
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn apply(&self, yard: &mut Arrangement, m: Move) {
+        for _ in 0..m.amount {
+            lift(yard, m.from, m.to, 1);
+        }
+    }
+}
+
+/// Moves all the crates of an instruction at once
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn apply(&self, yard: &mut Arrangement, m: Move) {
+        lift(yard, m.from, m.to, m.amount);
+    }
+}
+
+/// Lifts at most `capacity` crates at once, so that a capacity of 1 behaves as the
+/// CrateMover 9000, and a large enough one as the CrateMover 9001
+pub struct LimitedCrane {
+    pub capacity: usize,
+}
 
-    let mut tmp_stack = Vec::new();
+impl LimitedCrane {
+    /// Number of crates of each lift of the move
+    fn lifts(&self, m: Move) -> Vec<usize> {
+        assert!(self.capacity > 0, "A crane must lift at least one crate");
+        let mut lifts = vec![self.capacity; m.amount / self.capacity];
+        let rest = m.amount % self.capacity;
+        if rest > 0 {
+            lifts.push(rest);
+        }
+        return lifts;
+    }
+}
 
-    for _ in 0..amount {
-        let content = yard[from_idx - 1].pop().expect("Stack was empty D:");
-        tmp_stack.push(content);
+impl Crane for LimitedCrane {
+    fn apply(&self, yard: &mut Arrangement, m: Move) {
+        for n in self.lifts(m) {
+            lift(yard, m.from, m.to, n);
+        }
     }
-    for _ in 0..amount {
-        let content = tmp_stack.pop().expect("Stack was empty D:");
-        yard[to_idx - 1].push(content);
+
+    /// The last lift, on top of the stack, has to go back first
+    fn undo(&self, yard: &mut Arrangement, m: Move) {
+        for n in self.lifts(m).into_iter().rev() {
+            lift(yard, m.to, m.from, n);
+        }
     }
 }
 
-fn initialize_yard(initial_yard: &str) -> Arrangement{
-    let mut initial_yard = initial_yard.lines().rev();
-    let stack_numbers = initial_yard.next().unwrap();
+/// Crates of a line of the drawing, with their column. Lines may stop before the last stacks.
+fn crates_in_line(line: &str) -> Result<Vec<(usize, Crate)>, String> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut crates = Vec::new();
+    let mut col = 0;
+    while col < chars.len() {
+        match chars[col..] {
+            [' ', ..] => col += 1,
+            ['[', label, ']', ..] if label != ' ' => {
+                crates.push((col + 1, label));
+                col += 3;
+            },
+            _ => return Err(format!("expected a crate such as '[A]' at column {}", col + 1)),
+        }
+    }
+    return Ok(crates);
+}
 
-    let mut stack_indices = Vec::new();
-    let mut yard = Arrangement::new();
+/// Read the drawing of the stacks, whose last line numbers them. A crate belongs to the stack
+/// whose number is written below it (or next to it, for numbers of several digits).
+fn initialize_yard(drawing: &[&str]) -> Result<Arrangement, YardError> {
+    let (footer, lines) = drawing.split_last().ok_or(YardError::MissingFooter)?;
+    let footer_line = drawing.len();
 
-    for (i,c) in stack_numbers.char_indices() {
-        if c != ' ' {
-            yard.push(Stack::new());
-            stack_indices.push(i);
+    // Columns of the digits of each stack number
+    let mut labels: Vec<(usize, usize)> = Vec::new();
+    let chars: Vec<char> = footer.chars().collect();
+    let mut col = 0;
+    while col < chars.len() {
+        if chars[col] == ' ' {
+            col += 1;
+            continue;
+        }
+        let start = col;
+        while col < chars.len() && chars[col] != ' ' {
+            col += 1;
+        }
+        let label: String = chars[start..col].iter().collect();
+        if label.parse() != Ok(labels.len() + 1) {
+            return Err(YardError::InvalidDrawing {
+                line: footer_line,
+                reason: format!("expected stack number {}, found '{}'", labels.len() + 1, label),
+            });
         }
+        labels.push((start, col));
+    }
+    if labels.is_empty() {
+        return Err(YardError::MissingFooter);
     }
 
-    for line in initial_yard {
-        for (i, &idx_in_str) in (&stack_indices).into_iter().enumerate() {
-            let char_to_add = line.chars().nth(idx_in_str).unwrap();
-            if char_to_add != ' ' {
-                yard[i].push(char_to_add);
+    let mut yard = vec![Stack::new(); labels.len()];
+    for (line_idx, line) in lines.iter().enumerate().rev() {
+        let invalid = |reason: String| YardError::InvalidDrawing { line: line_idx + 1, reason };
+        let mut filled = vec![false; labels.len()];
+        for (col, label) in crates_in_line(line).map_err(invalid)? {
+            let distance = |&(start, end): &(usize, usize)| {
+                if col < start { start - col } else { (col + 1).saturating_sub(end) }
+            };
+            let stack = (0..labels.len())
+                .min_by_key(|&i| distance(&labels[i]))
+                .filter(|&i| distance(&labels[i]) <= 1)
+                .ok_or_else(|| invalid(format!("crate '{}' is not above a stack", label)))?;
+            if filled[stack] {
+                return Err(invalid(format!("two crates above stack {}", stack + 1)));
             }
+            filled[stack] = true;
+            yard[stack].push(label);
         }
     }
 
-    return yard;
+    return Ok(yard);
 }
 
+/// Moves with the number of their line in the input
+pub type Instructions = Vec<(usize, Move)>;
 
-pub fn main() -> String {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+/// Read a drawing of the yard alone, without instructions
+pub fn parse_drawing(drawing: &str) -> Result<Arrangement, YardError> {
+    let lines: Vec<&str> = drawing.lines().filter(|l| !l.trim().is_empty()).collect();
+    return initialize_yard(&lines);
+}
+
+pub fn parse(contents: &Input) -> Result<(Arrangement, Instructions), YardError> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let drawing_end = lines.iter().position(|l| l.is_empty()).unwrap_or(lines.len());
+    let yard = initialize_yard(&lines[..drawing_end])?;
+
+    let moves = lines.iter().enumerate()
+        .skip(drawing_end)
+        .filter(|(_, instr)| !instr.is_empty())
+        .map(|(idx, instr)| {
+            let m = instr.parse::<Move>().map_err(|reason| YardError::InvalidMove {
+                line: idx + 1, instr: String::from(*instr), reason,
+            })?;
+            return Ok((idx + 1, m));
+        })
+        .collect::<Result<Instructions, YardError>>()?;
+    return Ok((yard, moves));
+}
 
-    let mut contents = contents.split("\n\n");
-    let initial_yard = contents
-        .next()
-        .expect("Original arrangement not parsed.");
-    
-    let mut yard = initialize_yard(initial_yard);
-    
-    let instructions = contents.next().expect("Could not get instructions.").lines();
-    for instr in instructions {
-        do_instr(&mut yard, instr);
+/// Check the move of a line then apply it
+pub fn apply_line(crane: &dyn Crane, yard: &mut Arrangement, line: usize, m: Move) -> Result<(), YardError> {
+    m.check(yard).map_err(|reason| YardError::InvalidMove { line, instr: m.to_string(), reason })?;
+    crane.apply(yard, m);
+    return Ok(());
+}
+
+pub fn top_crates(yard: &Arrangement) -> String {
+    return yard.iter().filter_map(|stack| stack.last()).collect();
+}
+
+/// Yard once the crane has followed the instructions
+pub fn final_yard(crane: &dyn Crane, contents: &Input) -> Result<Arrangement, YardError> {
+    let (mut yard, moves) = parse(contents)?;
+    for (line, m) in moves {
+        apply_line(crane, &mut yard, line, m)?;
     }
+    return Ok(yard);
+}
 
-    let res = yard.into_iter()
-        .map(|y| y.into_iter().last().unwrap().to_string())
-        .fold(String::new(), |a,b| a + &b);
+/// Crates on top of each stack once the crane has followed the instructions
+pub fn run(crane: &dyn Crane, contents: &Input) -> Result<String, YardError> {
+    return final_yard(crane, contents).map(|yard| top_crates(&yard));
+}
+
+pub fn main() -> String {
+    return solve(&Input::read(INPUT_FILE));
+}
 
-    return res;
+pub fn solve(contents: &Input) -> String {
+    return run(&CrateMover9000, contents).unwrap_or_else(|err| panic!("{}", err));
 }
 
 pub fn main_bonus() -> String {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-
-    let mut contents = contents.split("\n\n");
-    let initial_yard = contents
-        .next()
-        .expect("Original arrangement not parsed.");
-    
-    let mut yard = initialize_yard(initial_yard);
-    
-    let instructions = contents.next().expect("Could not get instructions.").lines();
-    for instr in instructions {
-        do_instr_9001(&mut yard, instr);
-    }
-
-    let res = yard.into_iter()
-        .map(|y| y.into_iter().last().unwrap().to_string())
-        .fold(String::new(), |a,b| a + &b);
-
-    return res;
-}
\ No newline at end of file
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> String {
+    return run(&CrateMover9001, contents).unwrap_or_else(|err| panic!("{}", err));
+}
+
+/// Top crates when the crane can only lift `capacity` crates at once
+pub fn main_limited(capacity: usize) -> String {
+    return run(&LimitedCrane { capacity }, &Input::read(INPUT_FILE)).unwrap_or_else(|err| panic!("{}", err));
+}
+
+/// Draw the yard as in the input, with the number of each stack below it
+pub fn yard_to_string(yard: &Arrangement) -> String {
+    let height = yard.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lines: Vec<String> = (0..height).rev()
+        .map(|level| {
+            yard.iter()
+                .map(|stack| stack.get(level).map_or(String::from("   "), |c| format!("[{}]", c)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    lines.push((1..=yard.len()).map(|n| format!("{:^3}", n)).collect::<Vec<_>>().join(" "));
+
+    return lines.iter().map(|l| format!("{}\n", l.trim_end())).collect();
+}
+
+/// The yard after each move of the instructions
+pub struct Steps<'a> {
+    crane: &'a dyn Crane,
+    yard: Arrangement,
+    moves: std::vec::IntoIter<(usize, Move)>,
+}
+
+impl<'a> Steps<'a> {
+    pub fn new(crane: &'a dyn Crane, contents: &Input) -> Result<Self, YardError> {
+        let (yard, moves) = parse(contents)?;
+        return Ok(Self { crane, yard, moves: moves.into_iter() });
+    }
+}
+
+impl Iterator for Steps<'_> {
+    type Item = Result<(Move, Arrangement), YardError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, m) = self.moves.next()?;
+        return Some(apply_line(self.crane, &mut self.yard, line, m).map(|_| (m, self.yard.clone())));
+    }
+}
+
+/// Print the yard after each move. With a delay, each drawing replaces the previous one on
+/// the terminal, so that the crane is animated.
+pub fn print_steps<W: Write>(crane: &dyn Crane, contents: &Input, out: &mut W, delay: Option<Duration>) -> io::Result<()> {
+    for (step, result) in Steps::new(crane, contents)?.enumerate() {
+        let (m, yard) = result?;
+        if delay.is_some() {
+            // Clear the screen and go back to the top left corner
+            write!(out, "\x1b[2J\x1b[H")?;
+        }
+        writeln!(out, "#{} {}\n{}", step + 1, m, yard_to_string(&yard))?;
+        out.flush()?;
+        if let Some(delay) = delay {
+            thread::sleep(delay);
+        }
+    }
+    return Ok(());
+}
+
+pub fn main_animation(delay: Duration) -> io::Result<()> {
+    return print_steps(&CrateMover9001, &Input::read(INPUT_FILE), &mut io::stdout().lock(), Some(delay));
+}
+
+/// Draw the yard once the CrateMover 9001 has followed the instructions
+pub fn render(contents: &Input) -> String {
+    let yard = final_yard(&CrateMover9001, contents).unwrap_or_else(|err| panic!("{}", err));
+    return yard_to_string(&yard);
+}
+
+/// What the planner should reach
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// The crates on top of the stacks, as given by `top_crates`
+    Tops(String),
+    Arrangement(Arrangement),
+}
+
+impl Target {
+    fn is_reached(&self, yard: &Arrangement) -> bool {
+        return match self {
+            Target::Tops(tops) => top_crates(yard) == *tops,
+            Target::Arrangement(target) => yard == target,
+        };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanError {
+    /// Every yard reachable from the start was tried
+    Unreachable,
+    /// Gave up after having seen that many yards
+    TooManyStates(usize),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::Unreachable => write!(f, "the target cannot be reached"),
+            PlanError::TooManyStates(n) => write!(f, "no plan found among the first {} yards", n),
+        }
+    }
+}
+
+/// Every move the crane can do on the yard
+fn possible_moves(yard: &Arrangement) -> impl Iterator<Item = Move> + '_ {
+    let n = yard.len();
+    return (1..=n)
+        .flat_map(move |from| (1..=n).filter(move |&to| to != from).map(move |to| (from, to)))
+        .flat_map(move |(from, to)| (1..=yard[from - 1].len()).map(move |amount| Move { amount, from, to }));
+}
+
+/// A shortest program taking the yard to the target, found with a breadth-first search over
+/// the yards reachable from the start. It stops after having seen `max_states` yards.
+pub fn plan(crane: &dyn Crane, start: &Arrangement, target: &Target, max_states: usize) -> Result<Vec<Move>, PlanError> {
+    if target.is_reached(start) {
+        return Ok(Vec::new());
+    }
+    // Each yard seen, with the yard it comes from and the move which led to it
+    let mut seen: Vec<(Arrangement, Option<(usize, Move)>)> = vec![(start.clone(), None)];
+    let mut index: HashMap<Arrangement, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(current) = queue.pop_front() {
+        let moves: Vec<Move> = possible_moves(&seen[current].0).collect();
+        for m in moves {
+            let mut next = seen[current].0.clone();
+            crane.apply(&mut next, m);
+            if index.contains_key(&next) {
+                continue;
+            }
+            // Checked as soon as it is generated, so that the limit cannot hide it
+            if target.is_reached(&next) {
+                let mut moves = vec![m];
+                let mut idx = current;
+                while let Some((parent, m)) = seen[idx].1 {
+                    moves.push(m);
+                    idx = parent;
+                }
+                moves.reverse();
+                return Ok(moves);
+            }
+            if seen.len() >= max_states {
+                return Err(PlanError::TooManyStates(max_states));
+            }
+            index.insert(next.clone(), seen.len());
+            queue.push_back(seen.len());
+            seen.push((next, Some((current, m))));
+        }
+    }
+    return Err(PlanError::Unreachable);
+}
+
+/// The moves in the format of the puzzle, one per line
+pub fn program_to_string(moves: &[Move]) -> String {
+    return moves.iter().map(|m| format!("{}\n", m)).collect();
+}
+
+/// Run the program on the drawing of the yard as the puzzle's input, and tell whether it
+/// reaches the target
+pub fn verify_program(crane: &dyn Crane, start: &Arrangement, moves: &[Move], target: &Target) -> Result<bool, YardError> {
+    let input = Input::normalize(&format!("{}\n{}", yard_to_string(start), program_to_string(moves)));
+    return Ok(target.is_reached(&final_yard(crane, &input)?));
+}
+
+/// Yard along the instructions, which can be stepped through backwards and forwards
+pub struct Replay<'a> {
+    crane: &'a dyn Crane,
+    yard: Arrangement,
+    moves: Instructions,
+    /// Number of moves applied to the yard
+    position: usize,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(crane: &'a dyn Crane, contents: &Input) -> Result<Self, YardError> {
+        let (yard, moves) = parse(contents)?;
+        return Ok(Self { crane, yard, moves, position: 0 });
+    }
+
+    pub fn yard(&self) -> &Arrangement {
+        return &self.yard;
+    }
+
+    pub fn position(&self) -> usize {
+        return self.position;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.moves.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.moves.is_empty();
+    }
+
+    /// Apply the next move, if any
+    pub fn forward(&mut self) -> Result<Option<Move>, YardError> {
+        let (line, m) = match self.moves.get(self.position) {
+            None => return Ok(None),
+            Some(&instr) => instr,
+        };
+        apply_line(self.crane, &mut self.yard, line, m)?;
+        self.position += 1;
+        return Ok(Some(m));
+    }
+
+    /// Cancel the last move applied, if any
+    pub fn backward(&mut self) -> Option<Move> {
+        self.position = self.position.checked_sub(1)?;
+        let (_, m) = self.moves[self.position];
+        self.crane.undo(&mut self.yard, m);
+        return Some(m);
+    }
+
+    /// Go to the yard after the first `k` moves (or all of them, if there are fewer)
+    pub fn seek(&mut self, k: usize) -> Result<&Arrangement, YardError> {
+        let k = k.min(self.moves.len());
+        while self.position > k {
+            self.backward();
+        }
+        while self.position < k {
+            self.forward()?;
+        }
+        return Ok(&self.yard);
+    }
+
+    /// Number (from 1) and line of the first move lifting a crate with this label. The replay is
+    /// left just before that move.
+    pub fn first_touching(&mut self, label: Crate) -> Result<Option<(usize, usize)>, YardError> {
+        self.seek(0)?;
+        while let Some(&(line, m)) = self.moves.get(self.position) {
+            // An invalid move is reported when going forward
+            if m.check(&self.yard).is_ok() {
+                let stack = &self.yard[m.from - 1];
+                if stack[stack.len() - m.amount..].contains(&label) {
+                    return Ok(Some((self.position + 1, line)));
+                }
+            }
+            self.forward()?;
+        }
+        return Ok(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yard(stacks: &[&str]) -> Arrangement {
+        return stacks.iter().map(|s| s.chars().collect()).collect();
+    }
+
+    /// Twelve stacks, with lines of different lengths and numbers of two digits
+    const WIDE: &str = "                                        [L]
+[A]     [C]                             [K] [M]
+[B] [D] [E] [F] [G] [H] [I] [J] [N] [O] [P] [Q]
+ 1   2   3   4   5   6   7   8   9   10  11  12";
+
+    #[test]
+    fn wide_ragged_drawing() {
+        let expected = yard(&["BA", "D", "EC", "F", "G", "H", "I", "J", "N", "O", "PKL", "QM"]);
+        assert_eq!(parse_drawing(WIDE), Ok(expected.clone()));
+        assert_eq!(top_crates(&expected), "ADCFGHIJNOLM");
+    }
+
+    #[test]
+    fn drawn_yards_read_back() {
+        let yards = [
+            parse_drawing(WIDE).unwrap(),
+            parse(&Input::read("data/test05.txt")).unwrap().0,
+            final_yard(&CrateMover9001, &Input::read("data/test05.txt")).unwrap(),
+            yard(&["AB", "", "C", "", "", "", "", "", "", "DEF"]),
+        ];
+        for y in yards {
+            assert_eq!(parse_drawing(&yard_to_string(&y)), Ok(y));
+        }
+    }
+
+    #[test]
+    fn invalid_drawings() {
+        assert_eq!(parse_drawing("[A]     [B]\n 1   2"), Err(YardError::InvalidDrawing {
+            line: 1, reason: String::from("crate 'B' is not above a stack"),
+        }));
+        assert_eq!(parse_drawing("[A]\n[B] [C]\n 1   3"), Err(YardError::InvalidDrawing {
+            line: 3, reason: String::from("expected stack number 2, found '3'"),
+        }));
+        assert_eq!(parse_drawing("[A]\n[B  C]\n 1   2"), Err(YardError::InvalidDrawing {
+            line: 2, reason: String::from("expected a crate such as '[A]' at column 1"),
+        }));
+        assert_eq!(parse_drawing("[A] [B]"), Err(YardError::InvalidDrawing {
+            line: 1, reason: String::from("expected stack number 1, found '[A]'"),
+        }));
+        assert_eq!(parse_drawing(""), Err(YardError::MissingFooter));
+    }
+
+    /// The example drawing followed by the given moves, from line 6
+    fn with_moves(moves: &str) -> Input {
+        return Input::normalize(&format!("    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n\n{}", moves));
+    }
+
+    #[test]
+    fn invalid_moves_by_line() {
+        let err = run(&CrateMover9000, &with_moves("move 1 from 2 to 1\nmove 3 from 2 to 3\n")).unwrap_err();
+        assert_eq!(err, YardError::InvalidMove {
+            line: 7,
+            instr: String::from("move 3 from 2 to 3"),
+            reason: MoveError::NotEnoughCrates { stack: 2, available: 2 },
+        });
+        assert_eq!(err.to_string(), "line 7 'move 3 from 2 to 3': stack 2 only has 2 crates");
+
+        let err = run(&CrateMover9001, &with_moves("move 1 from 4 to 1\n")).unwrap_err();
+        assert_eq!(err, YardError::InvalidMove {
+            line: 6, instr: String::from("move 1 from 4 to 1"), reason: MoveError::NoSuchStack(4),
+        });
+
+        let err = run(&CrateMover9000, &with_moves("move 1 from 2 to 1\nmove x from 1 to 2\n")).unwrap_err();
+        assert_eq!(err, YardError::InvalidMove {
+            line: 7, instr: String::from("move x from 1 to 2"), reason: MoveError::Syntax,
+        });
+    }
+
+    #[test]
+    fn plan_within_the_limit() {
+        let start = yard(&["AB", "C"]);
+        // One move away, found even though no other yard may be kept
+        let target = Target::Arrangement(yard(&["A", "CB"]));
+        assert_eq!(plan(&CrateMover9000, &start, &target, 1), Ok(vec![Move { amount: 1, from: 1, to: 2 }]));
+        assert_eq!(plan(&CrateMover9000, &start, &Target::Tops(String::from("BC")), 1), Ok(Vec::new()));
+
+        let target = Target::Tops(String::from("CA"));
+        let moves = plan(&CrateMover9001, &start, &target, 100).unwrap();
+        assert_eq!(moves.len(), 3);
+        assert_eq!(verify_program(&CrateMover9001, &start, &moves, &target), Ok(true));
+        assert_eq!(plan(&CrateMover9001, &start, &target, 1), Err(PlanError::TooManyStates(1)));
+        assert_eq!(plan(&CrateMover9001, &start, &Target::Tops(String::from("D")), 100), Err(PlanError::Unreachable));
+    }
+
+    #[test]
+    fn limited_crane_seeks_back_and_forth() {
+        let contents = Input::normalize("\
+[E]
+[D]
+[C]
+[B]
+[A]     [X]
+ 1   2   3
+
+move 5 from 1 to 2
+move 3 from 2 to 3
+");
+        let crane = LimitedCrane { capacity: 2 };
+        let mut replay = Replay::new(&crane, &contents).unwrap();
+        let start = yard(&["ABCDE", "", "X"]);
+        // Lifts of D E, then B C, then A
+        let after_first = yard(&["", "DEBCA", "X"]);
+        // Lifts of C A, then B
+        let after_second = yard(&["", "DE", "XCAB"]);
+
+        assert_eq!(replay.seek(2), Ok(&after_second));
+        assert_eq!(replay.seek(0), Ok(&start));
+        assert_eq!(replay.seek(1), Ok(&after_first));
+        assert_eq!(replay.seek(5), Ok(&after_second));
+        assert_eq!(replay.position(), 2);
+        assert_eq!(replay.backward(), Some(Move { amount: 3, from: 2, to: 3 }));
+        assert_eq!(replay.yard(), &after_first);
+        assert_eq!(replay.seek(0), Ok(&start));
+        assert_eq!(replay.backward(), None);
+        assert_eq!(replay.first_touching('B'), Ok(Some((1, 8))));
+        assert_eq!(replay.first_touching('X'), Ok(None));
+    }
+
+    #[test]
+    fn example() {
+        let contents = Input::read("data/test05.txt");
+        assert_eq!(solve(&contents), "CMZ");
+        assert_eq!(solve_bonus(&contents), "MCD");
+    }
+}
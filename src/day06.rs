@@ -1,26 +1,157 @@
-use std::fs;
-use itertools::Itertools;
+use crate::input::Input;
+use std::io::{self, BufReader, Read};
 const INPUT_FILE: &str = "data/input06.txt";
 
-fn index_first_marker(s: &str, len_substring: usize) -> usize {
-    for i in len_substring..=s.len() {
-        // Count the number of unique characters in the substring
-        let n = s[(i-len_substring)..i].chars().unique().count();
-        if n == len_substring { return i; }
+/// The last `size` bytes received, with the number of times each value appears among them,
+/// so that telling whether they are all different takes constant time.
+pub struct Window {
+    size: usize,
+    /// Ring buffer of the last bytes
+    last: Vec<u8>,
+    counts: [usize; 256],
+    /// Number of values appearing more than once
+    duplicates: usize,
+    received: usize,
+}
+
+impl Window {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "A marker has at least one character");
+        Self { size, last: vec![0; size], counts: [0; 256], duplicates: 0, received: 0 }
+    }
+
+    /// Add a byte, and tell whether the last `size` bytes are all different
+    pub fn push(&mut self, byte: u8) -> bool {
+        let slot = self.received % self.size;
+        if self.received >= self.size {
+            let old = self.last[slot] as usize;
+            if self.counts[old] == 2 {
+                self.duplicates -= 1;
+            }
+            self.counts[old] -= 1;
+        }
+        self.last[slot] = byte;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+        self.received += 1;
+        return self.received >= self.size && self.duplicates == 0;
+    }
+
+    /// Number of bytes received so far
+    pub fn received(&self) -> usize {
+        return self.received;
+    }
+}
+
+/// Ends of lines are not part of the signal: they are skipped, and not counted in positions,
+/// whether the signal is in memory or read from a stream
+fn is_line_ending(byte: u8) -> bool {
+    return byte == b'\n' || byte == b'\r';
+}
+
+/// Number of bytes read at the end of each marker, i.e. of each run of `size` different bytes.
+/// Line endings are skipped.
+pub struct Markers<I: Iterator<Item = u8>> {
+    bytes: I,
+    window: Window,
+}
+
+impl<I: Iterator<Item = u8>> Markers<I> {
+    pub fn new<T: IntoIterator<IntoIter = I>>(bytes: T, size: usize) -> Self {
+        return Self { bytes: bytes.into_iter(), window: Window::new(size) };
     }
-    return 0;
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Markers<I> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        for byte in self.bytes.by_ref().filter(|&b| !is_line_ending(b)) {
+            if self.window.push(byte) {
+                return Some(self.window.received());
+            }
+        }
+        return None;
+    }
+}
+
+pub fn index_first_marker(s: &str, len_substring: usize) -> Option<usize> {
+    return Markers::new(s.bytes(), len_substring).next();
 }
 
 pub fn main() -> usize {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
-    return index_first_marker(&contents, 4);
+pub fn solve(contents: &Input) -> usize {
+    return index_first_marker(contents, 4).expect("No start-of-packet marker");
 }
 
 pub fn main_bonus() -> usize {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> usize {
+    return index_first_marker(contents, 14).expect("No start-of-message marker");
+}
+
+/// Look for the first marker of each size in a single pass over the reader, calling
+/// `found(size, position)` as soon as one is found, with positions counted as for `Markers`.
+/// Reading stops once all of them are found, so that it also works on an endless stream.
+/// Sizes without a marker get None.
+pub fn first_markers<R, F>(reader: R, sizes: &[usize], mut found: F) -> io::Result<Vec<(usize, Option<usize>)>>
+    where R: Read, F: FnMut(usize, usize)
+{
+    let mut windows: Vec<Window> = sizes.iter().map(|&size| Window::new(size)).collect();
+    let mut positions: Vec<Option<usize>> = vec![None; sizes.len()];
+    let mut pending = sizes.len();
 
-    return index_first_marker(&contents, 14);
-}
\ No newline at end of file
+    let mut bytes = BufReader::new(reader).bytes();
+    // Do not wait for bytes which are not needed
+    while pending > 0 {
+        let byte = match bytes.next() {
+            Some(byte) => byte?,
+            None => break,
+        };
+        if is_line_ending(byte) {
+            continue;
+        }
+        for (i, window) in windows.iter_mut().enumerate() {
+            if positions[i].is_none() && window.push(byte) {
+                positions[i] = Some(window.received());
+                found(sizes[i], window.received());
+                pending -= 1;
+            }
+        }
+    }
+    return Ok(sizes.iter().copied().zip(positions).collect());
+}
+
+fn first_marker_reader<R: Read>(reader: R, size: usize) -> io::Result<usize> {
+    let (_, position) = first_markers(reader, &[size], |_, _| ())?[0];
+    return position.ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "no marker in the stream"));
+}
+
+pub fn solve_reader<R: Read>(reader: R) -> io::Result<usize> {
+    return first_marker_reader(reader, 4);
+}
+
+pub fn solve_bonus_reader<R: Read>(reader: R) -> io::Result<usize> {
+    return first_marker_reader(reader, 14);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_endings_skipped_in_memory_and_in_streams() {
+        let signal = "mjqjp\nqmgbl\r\njsphdztnvjfqwrcgsmlb";
+        let in_memory: Vec<usize> = [4, 14].iter().map(|&n| index_first_marker(signal, n).unwrap()).collect();
+        let streamed = first_markers(signal.as_bytes(), &[4, 14], |_, _| ()).unwrap();
+        assert_eq!(in_memory, vec![7, 19]);
+        assert_eq!(streamed, vec![(4, Some(7)), (14, Some(19))]);
+        assert_eq!(index_first_marker("ab\ncd", 4), Some(4));
+    }
+}
@@ -1,28 +1,37 @@
-use std::str::Lines;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
 
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input07.txt";
 
 const TOTAL_DISK_SPACE: u32 = 70000000;
 const GOAL_DISK_SPACE: u32 = 30000000;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    File { size: u32 },
+    /// Total size of the directory, cached once computed
+    Dir { total: Option<u32> },
+}
+
 #[derive(Debug)]
 struct FileSystNode {
     idx: usize,
-    /// Identifying value
-    path: String,
-    size: u32,
+    /// Name in its parent directory, empty for the root
+    name: String,
+    kind: NodeKind,
     parent: Option<usize>,
     children: Vec<usize>,
 }
 
 impl FileSystNode {
-    fn new(idx: usize, path: &str, size: u32) -> Self {
+    fn new(idx: usize, name: &str, kind: NodeKind, parent: Option<usize>) -> Self {
         Self {
             idx,
-            path: String::from(path),
-            size,
-            parent: None,
+            name: String::from(name),
+            kind,
+            parent,
             children: Vec::new(),
         }
     }
@@ -32,116 +41,456 @@ impl FileSystNode {
     }
 
     fn is_dir(&self) -> bool {
-        return !(self.children.is_empty());
+        return matches!(self.kind, NodeKind::Dir { .. });
     }
 }
 
 #[derive(Debug, Default)]
-struct FileSystem {
+pub struct FileSystem {
     files: Vec<FileSystNode>,
+    /// Index of each node from its parent and its name, so that following a path of n names
+    /// takes n lookups
+    index: HashMap<(usize, String), usize>,
 }
 
 impl FileSystem {
     fn new() -> Self {
-        Self {files: Vec::new(),}
+        Self::default()
     }
 
-    /// Get node index from path
+    /// Get node index from path, e.g. `/a/e`, the root being the empty path
     fn get_node(&self, path: &str) -> Option<usize> {
-        return (&self.files).into_iter().position(|fnode| fnode.path == path);
+        let root = (!self.files.is_empty()).then_some(0);
+        return path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(root?, |dir, name| self.get_child(dir, name));
+    }
+
+    fn parent(&self, idx: usize) -> Option<usize> {
+        return self.files[idx].parent;
+    }
+
+    /// Path of a node, made of the names of its ancestors
+    fn path(&self, idx: usize) -> String {
+        let mut names = vec![self.files[idx].name.as_str()];
+        let mut current = idx;
+        while let Some(parent) = self.parent(current) {
+            names.push(&self.files[parent].name);
+            current = parent;
+        }
+        names.reverse();
+        return names.join("/");
+    }
+
+    fn child_path(&self, parent: usize, name: &str) -> String {
+        return self.path(parent) + "/" + name;
     }
 
-    /// Add new node assuming no node with same identifier (i.e. path) exists.
-    fn add_node(&mut self, path: &str, size: u32) -> usize{
+    /// Get a node from its name in a directory
+    fn get_child(&self, parent: usize, name: &str) -> Option<usize> {
+        return self.index.get(&(parent, String::from(name))).copied();
+    }
+
+    /// Add new node assuming no node with same path exists.
+    fn add_node(&mut self, parent: Option<usize>, name: &str, kind: NodeKind) -> usize {
         let idx = self.files.len();
-        // self.files.push(Box::new(FileSystNode::new(idx, path, size)));
-        self.files.push(FileSystNode::new(idx, path, size));
+        self.files.push(FileSystNode::new(idx, name, kind, parent));
+        if let Some(parent) = parent {
+            self.files[parent].add_child(idx);
+            self.index.insert((parent, String::from(name)), idx);
+        }
+
+        // The totals of the directories above have changed. Those above a directory without
+        // a total have none either.
+        let mut current = parent;
+        while let Some(dir) = current {
+            if self.files[dir].kind == (NodeKind::Dir { total: None }) {
+                break;
+            }
+            self.files[dir].kind = NodeKind::Dir { total: None };
+            current = self.parent(dir);
+        }
         return idx;
     }
+
+    /// Size of a file, or total size of a directory
+    fn size(&self, idx: usize) -> u32 {
+        return match self.files[idx].kind {
+            NodeKind::File { size } | NodeKind::Dir { total: Some(size) } => size,
+            NodeKind::Dir { total: None } => self.files[idx].children.iter().map(|&c| self.size(c)).sum(),
+        };
+    }
+
+    /// Nodes below a directory, at any depth, each directory being followed by its contents
+    fn descendants(&self, idx: usize) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut to_visit: Vec<usize> = self.files[idx].children.iter().rev().copied().collect();
+        while let Some(current) = to_visit.pop() {
+            found.push(current);
+            to_visit.extend(self.files[current].children.iter().rev());
+        }
+        return found;
+    }
+
+    fn list_under(&self, path: &str, dirs: bool) -> Option<Vec<(String, u32)>> {
+        let idx = self.get_node(path).filter(|&idx| self.files[idx].is_dir())?;
+        return Some(self.descendants(idx).into_iter()
+            .filter(|&i| self.files[i].is_dir() == dirs)
+            .map(|i| (self.path(i), self.size(i)))
+            .collect());
+    }
+
+    /// Path and size of each file under a directory (the root being the empty path), or None
+    /// when there is no such directory
+    pub fn files_under(&self, path: &str) -> Option<Vec<(String, u32)>> {
+        return self.list_under(path, false);
+    }
+
+    /// Path and total size of each directory under a directory
+    pub fn dirs_under(&self, path: &str) -> Option<Vec<(String, u32)>> {
+        return self.list_under(path, true);
+    }
 }
 
 fn is_cmd(l: &str) -> bool {
-    return l.chars().next() == Some('&');
+    return l.starts_with('$');
 }
 
-fn parse_dir(l: &mut Lines, fsyst: &mut FileSystem, cur_dir: usize) {
-    let path_prefix = fsyst.files[cur_dir].path.clone() + "/";
-    while let Some(line) = l.next() {
-        let basename = line.split(" ").last().unwrap();
-        let path = path_prefix.clone() + basename;
+/// Something in the transcript which does not match what was seen before
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Line of the transcript, from 1
+    pub line: usize,
+    pub message: String,
+}
 
-        // Leave dir
-        if basename == ".." {
-            break;
-        }
-        // Change dir
-        else if line.starts_with("$ cd ") {
-            let next_dir = fsyst.get_node(&path).unwrap();
-            parse_dir(l, fsyst, next_dir);
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Directory reached from `cur_dir` by a path such as `a/b`, `../c` or `/d`. Directories which
+/// were not listed yet are created.
+fn change_dir(fsyst: &mut FileSystem, cur_dir: usize, path: &str) -> Result<usize, String> {
+    let mut dir = if path.starts_with('/') { 0 } else { cur_dir };
+    for name in path.split('/').filter(|n| !n.is_empty() && *n != ".") {
+        dir = match name {
+            ".." => fsyst.parent(dir).ok_or("cannot go above the root")?,
+            _ => match fsyst.get_child(dir, name) {
+                Some(child) if fsyst.files[child].is_dir() => child,
+                Some(_) => return Err(format!("'{}' is a file", fsyst.child_path(dir, name))),
+                None => fsyst.add_node(Some(dir), name, NodeKind::Dir { total: None }),
+            },
+        };
+    }
+    return Ok(dir);
+}
+
+/// Add a line listed by `ls` to the directory, if it was not already known
+fn add_listed(fsyst: &mut FileSystem, cur_dir: usize, line: &str) -> Result<(), String> {
+    let (info, name) = line.split_once(' ').ok_or_else(|| format!("cannot read '{}'", line))?;
+    let kind = match info {
+        "dir" => NodeKind::Dir { total: None },
+        size => NodeKind::File {
+            size: size.parse().map_err(|_| format!("'{}' is not a size", size))?,
+        },
+    };
+    let existing = match fsyst.get_child(cur_dir, name) {
+        None => {
+            fsyst.add_node(Some(cur_dir), name, kind);
+            return Ok(());
+        },
+        Some(idx) => fsyst.files[idx].kind,
+    };
+    let path = fsyst.child_path(cur_dir, name);
+    return match (existing, kind) {
+        (NodeKind::Dir { .. }, NodeKind::Dir { .. }) => Ok(()),
+        (NodeKind::File { size: old }, NodeKind::File { size: new }) if old == new => Ok(()),
+        (NodeKind::File { size: old }, NodeKind::File { size: new }) =>
+            Err(format!("'{}' was listed with size {}, now {} (keeping {})", path, old, new, old)),
+        (NodeKind::Dir { .. }, _) => Err(format!("'{}' was listed as a directory, now as a file", path)),
+        (NodeKind::File { .. }, _) => Err(format!("'{}' was listed as a file, now as a directory", path)),
+    };
+}
+
+/// What the lines which are not commands are
+enum Output {
+    None,
+    Listing,
+    /// Output of an unknown command
+    Ignored,
+}
+
+/// Rebuild the file system from the transcript, line by line. Lines which do not fit are
+/// skipped and reported.
+fn parse_lines<'a, I: Iterator<Item = &'a str>>(lines: I, fsyst: &mut FileSystem) -> Vec<Diagnostic> {
+    let root = fsyst.add_node(None, "", NodeKind::Dir { total: None });
+    let mut diagnostics = Vec::new();
+    let mut cur_dir = root;
+    let mut output = Output::None;
+
+    for (idx, line) in lines.enumerate() {
+        let result = if is_cmd(line) {
+            let mut words = line[1..].split_whitespace();
+            output = Output::None;
+            match (words.next(), words.next(), words.next()) {
+                (Some("cd"), Some(path), None) => change_dir(fsyst, cur_dir, path).map(|dir| cur_dir = dir),
+                (Some("ls"), None, None) => {
+                    output = Output::Listing;
+                    Ok(())
+                },
+                _ => {
+                    output = Output::Ignored;
+                    Err(format!("unknown command '{}', its output is ignored", line[1..].trim()))
+                },
+            }
+        } else {
+            match output {
+                Output::Listing => add_listed(fsyst, cur_dir, line),
+                // Already reported with the command
+                Output::Ignored => Ok(()),
+                Output::None => Err(format!("'{}' does not follow a command with an output", line)),
+            }
+        };
+        if let Err(message) = result {
+            diagnostics.push(Diagnostic { line: idx + 1, message });
         }
-        // Add file to system 
-        else if line != "$ ls" {
-            let fsize: u32 = match line.split(" ").next().unwrap().parse() {
-                Ok(n) => n,
-                Err(_) => 0, // The node is a dir
-            };
-            let idx = fsyst.add_node(&path,  fsize);
-            fsyst.files[cur_dir].add_child(idx);
+    }
+    return diagnostics;
+}
+
+/// Compute the total size of every directory. Children are added after their parent, so
+/// going through the nodes backwards sees every child before its parent.
+fn compute_dir_sizes(fsyst: &mut FileSystem) {
+    for idx in (0..fsyst.files.len()).rev() {
+        if fsyst.files[idx].is_dir() {
+            let total = fsyst.files[idx].children.iter().map(|&c| fsyst.size(c)).sum();
+            fsyst.files[idx].kind = NodeKind::Dir { total: Some(total) };
         }
     }
 }
 
-fn parse_lines(l: &mut Lines, fsyst: &mut FileSystem) {
-    if let Some(_) = l.next() {
-        let root = fsyst.add_node("", 0);
-        parse_dir(l, fsyst, root);
+impl FileSystem {
+    /// Read the transcript of the terminal, and compute the size of every directory. Lines
+    /// which do not fit with the rest are reported.
+    pub fn parse_transcript(contents: &Input) -> (Self, Vec<Diagnostic>) {
+        let mut fsyst = FileSystem::new();
+        let diagnostics = parse_lines(contents.lines(), &mut fsyst);
+        compute_dir_sizes(&mut fsyst);
+        return (fsyst, diagnostics);
+    }
+
+    pub fn from_transcript(contents: &Input) -> Self {
+        return Self::parse_transcript(contents).0;
+    }
+
+    /// Total size of every directory, including the root
+    fn dir_sizes(&self) -> impl Iterator<Item = u32> + '_ {
+        return self.files.iter().filter(|fnode| fnode.is_dir()).map(|fnode| self.size(fnode.idx));
+    }
+}
+
+/// A node of the hierarchy, as exported to JSON. Directories have children, files do not.
+#[derive(Clone, Debug, Serialize)]
+pub struct Entry {
+    pub name: String,
+    /// Size of a file, or total size of a directory
+    pub size: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<Entry>>,
+}
+
+/// Size rounded up with a unit, as `du -h` does, e.g. `584`, `1.5K` or `47M`
+fn human_size(bytes: u32) -> String {
+    const UNITS: [&str; 4] = ["", "K", "M", "G"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        return bytes.to_string();
     }
+    // One decimal below 10, none above, always rounding up
+    let rounded = if size < 10.0 { (size * 10.0).ceil() / 10.0 } else { size.ceil() };
+    if rounded >= 1024.0 && unit < UNITS.len() - 1 {
+        return format!("1.0{}", UNITS[unit + 1]);
+    }
+    if rounded < 10.0 {
+        return format!("{:.1}{}", rounded, UNITS[unit]);
+    }
+    return format!("{:.0}{}", rounded, UNITS[unit]);
 }
 
-fn compute_dir_size(fsyst: &mut FileSystem, cur_node: usize) -> u32 {
-    if fsyst.files[cur_node].is_dir() {
-        fsyst.files[cur_node].size = fsyst.files[cur_node].children.clone()
-            .into_iter()
-            .map(|idx| compute_dir_size(fsyst, idx))
-            .sum();
+impl FileSystem {
+    /// Name to show for a node, `/` for the root
+    fn display_name(&self, idx: usize) -> &str {
+        return if idx == 0 { "/" } else { &self.files[idx].name };
+    }
+
+    /// Indented view of the hierarchy, in the format of the puzzle, e.g.
+    /// ```text
+    /// - / (dir, size=48381165)
+    ///   - a (dir, size=94853)
+    ///     - f (file, size=29116)
+    /// ```
+    pub fn tree(&self) -> String {
+        let mut out = String::new();
+        let mut to_visit = if self.files.is_empty() { Vec::new() } else { vec![(0, 0)] };
+        while let Some((idx, depth)) = to_visit.pop() {
+            let kind = if self.files[idx].is_dir() { "dir" } else { "file" };
+            out += &format!("{}- {} ({}, size={})\n", "  ".repeat(depth), self.display_name(idx), kind, self.size(idx));
+            to_visit.extend(self.files[idx].children.iter().rev().map(|&c| (c, depth + 1)));
+        }
+        return out;
     }
-    return fsyst.files[cur_node].size;
+
+    /// Total size of every directory with its path, largest first, as `du -h` would print them
+    pub fn du(&self) -> String {
+        let mut dirs: Vec<(u32, String)> = self.files.iter()
+            .filter(|fnode| fnode.is_dir())
+            .map(|fnode| (self.size(fnode.idx), if fnode.idx == 0 { String::from("/") } else { self.path(fnode.idx) }))
+            .collect();
+        dirs.sort_by(|(size_a, path_a), (size_b, path_b)| size_b.cmp(size_a).then(path_a.cmp(path_b)));
+        return dirs.iter()
+            .map(|(size, path)| format!("{}\t{}\n", human_size(*size), path))
+            .collect();
+    }
+
+    fn entry(&self, idx: usize) -> Entry {
+        let fnode = &self.files[idx];
+        return Entry {
+            name: String::from(self.display_name(idx)),
+            size: self.size(idx),
+            children: fnode.is_dir().then(|| fnode.children.iter().map(|&c| self.entry(c)).collect()),
+        };
+    }
+
+    /// The whole hierarchy from the root, or None if nothing was read
+    pub fn entries(&self) -> Option<Entry> {
+        return (!self.files.is_empty()).then(|| self.entry(0));
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(&self.entries()).unwrap();
+    }
+}
+
+/// Indented view of the file system of a transcript
+pub fn render_tree(contents: &Input) -> String {
+    return FileSystem::from_transcript(contents).tree();
+}
+
+/// Directories of a transcript, largest first
+pub fn render_du(contents: &Input) -> String {
+    return FileSystem::from_transcript(contents).du();
+}
+
+/// File system of the input as JSON
+pub fn main_json() -> String {
+    return FileSystem::from_transcript(&Input::read(INPUT_FILE)).to_json();
 }
 
 
 pub fn main() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-    let mut contents = contents.lines();
-    
-    let mut fsyst = FileSystem::new();
-    parse_lines(&mut contents, &mut fsyst);
-    compute_dir_size(&mut fsyst, 0);
-
-    return fsyst.files.into_iter()
-        .filter(|fnode| fnode.is_dir())
-        .map(|fnode| fnode.size)
+    return solve(&Input::read(INPUT_FILE));
+}
+
+pub fn solve(contents: &Input) -> u32 {
+    let fsyst = FileSystem::from_transcript(contents);
+
+    return fsyst.dir_sizes()
         .filter(|s| *s <= 100_000)
         .sum();
 }
 
 pub fn main_bonus() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-    let mut contents = contents.lines();
-    
-    let mut fsyst = FileSystem::new();
-    parse_lines(&mut contents, &mut fsyst);
-    compute_dir_size(&mut fsyst, 0);
-
-    let used_disk_space = fsyst.files[0].size;
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> u32 {
+    let fsyst = FileSystem::from_transcript(contents);
+
+    let used_disk_space = fsyst.size(0);
     let current_free_space = TOTAL_DISK_SPACE - used_disk_space;
     let free_at_least = GOAL_DISK_SPACE - current_free_space;
 
-    return fsyst.files.into_iter()
-        .filter(|fnode| fnode.is_dir())
-        .map(|fnode| fnode.size)
+    return fsyst.dir_sizes()
         .filter(|s| *s >= free_at_least)
         .min()
         .unwrap();
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSCRIPT: &str = "\
+$ cd /
+$ ls
+dir a
+10 f
+$ cd a/x/../y
+$ ls
+5 g
+$ cd /
+$ ls
+11 f
+dir a
+$ cd f
+$ cd ..
+$ pwd
+/
+$ cd /a
+stray
+";
+
+    fn diagnostic(line: usize, message: &str) -> Diagnostic {
+        return Diagnostic { line, message: String::from(message) };
+    }
+
+    fn owned(list: &[(&str, u32)]) -> Vec<(String, u32)> {
+        return list.iter().map(|&(path, size)| (String::from(path), size)).collect();
+    }
+
+    #[test]
+    fn diagnostics() {
+        let (fsyst, diagnostics) = FileSystem::parse_transcript(&Input::normalize(TRANSCRIPT));
+        assert_eq!(diagnostics, vec![
+            diagnostic(10, "'/f' was listed with size 10, now 11 (keeping 10)"),
+            diagnostic(12, "'/f' is a file"),
+            diagnostic(13, "cannot go above the root"),
+            diagnostic(14, "unknown command 'pwd', its output is ignored"),
+            diagnostic(17, "'stray' does not follow a command with an output"),
+        ]);
+        assert_eq!(fsyst.files_under(""), Some(owned(&[("/a/y/g", 5), ("/f", 10)])));
+        assert_eq!(fsyst.dirs_under(""), Some(owned(&[("/a", 5), ("/a/x", 0), ("/a/y", 5)])));
+        assert_eq!(fsyst.size(0), 15);
+        assert_eq!(fsyst.files_under("/a/y"), Some(owned(&[("/a/y/g", 5)])));
+        assert_eq!(fsyst.files_under("/a/x"), Some(Vec::new()));
+        assert_eq!(fsyst.files_under("/f"), None);
+        assert_eq!(fsyst.dirs_under("/a/y/g"), None);
+        assert_eq!(fsyst.dirs_under("/b"), None);
+    }
+
+    #[test]
+    fn conflicting_kinds() {
+        let transcript = "$ cd /\n$ ls\ndir a\n1 b\n$ ls\n1 a\ndir b\nabc c\n";
+        let (_, diagnostics) = FileSystem::parse_transcript(&Input::normalize(transcript));
+        assert_eq!(diagnostics, vec![
+            diagnostic(6, "'/a' was listed as a directory, now as a file"),
+            diagnostic(7, "'/b' was listed as a file, now as a directory"),
+            diagnostic(8, "'abc' is not a size"),
+        ]);
+    }
+
+    #[test]
+    fn example() {
+        let contents = Input::read("data/test07.txt");
+        assert_eq!(solve(&contents), 95437);
+        assert_eq!(solve_bonus(&contents), 24933642);
+        assert!(FileSystem::parse_transcript(&contents).1.is_empty());
+    }
+}
@@ -1,11 +1,11 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input08.txt";
 
 use itertools::izip;
 use ndarray::{Array, Array2, ArrayView1, ArrayViewMut1, Axis, s};
 
 fn parse_tree_heights(s: &str) -> Array2<i32> {
-    let ncol = s.find("\n").unwrap();
+    let ncol = s.lines().next().unwrap().len();
     let nrow = s.lines().count();
 
     let data: Vec<i32> = s.chars().filter(|&c| c != '\n')
@@ -76,10 +76,10 @@ fn get_visibility(tree_heights: &Array2<i32>) -> Array2<bool> {
 }
 
 fn get_scores(tree_heights: &Array2<i32>) -> Array2<i32> {
-    let mut scores_left: Array2<i32> = 0*tree_heights;
-    let mut scores_right: Array2<i32> = 0*tree_heights;
-    let mut scores_up: Array2<i32> = 0*tree_heights;
-    let mut scores_down: Array2<i32> = 0*tree_heights;
+    let mut scores_left: Array2<i32> = Array2::zeros(tree_heights.raw_dim());
+    let mut scores_right: Array2<i32> = Array2::zeros(tree_heights.raw_dim());
+    let mut scores_up: Array2<i32> = Array2::zeros(tree_heights.raw_dim());
+    let mut scores_down: Array2<i32> = Array2::zeros(tree_heights.raw_dim());
 
     for (i, row) in tree_heights.axis_iter(Axis(0)).enumerate() {
         set_vec_score(
@@ -97,10 +97,11 @@ fn get_scores(tree_heights: &Array2<i32>) -> Array2<i32> {
 }
 
 pub fn main() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
-    let tree_heights: Array2<i32> = parse_tree_heights(&contents);
+pub fn solve(contents: &Input) -> u32 {
+    let tree_heights: Array2<i32> = parse_tree_heights(contents);
     let visibility = get_visibility(&tree_heights);
 
     // let mut scores: Array1<i32> = Array::from(vec![0,0,0,0,0]);
@@ -111,10 +112,11 @@ pub fn main() -> u32 {
 }
 
 pub fn main_bonus() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
 
-    let tree_heights: Array2<i32> = parse_tree_heights(&contents);
+pub fn solve_bonus(contents: &Input) -> u32 {
+    let tree_heights: Array2<i32> = parse_tree_heights(contents);
     let scores = get_scores(&tree_heights);
     // println!("{}", scores);
 
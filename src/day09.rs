@@ -1,4 +1,4 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input09.txt";
 
 use nalgebra::{DMatrix, Vector2};
@@ -13,7 +13,7 @@ const D: Vector2<i32> = Vector2::new( 0, -1);
 const Z: Vector2<i32> = Vector2::new(0,0);
 
 fn parse_instruction(instr: &str) -> (i32,Vector2<i32>) {
-    return match (instr.chars().nth(0), &instr[2..]) {
+    return match (instr.chars().next(), &instr[2..]) {
         (Some('R'), n) => (n.parse().unwrap(), R),
         (Some('L'), n) => (n.parse().unwrap(), L),
         (Some('U'), n) => (n.parse().unwrap(), U),
@@ -25,8 +25,8 @@ fn parse_instruction(instr: &str) -> (i32,Vector2<i32>) {
 /// Compute the bounding box of the Head, which contains a bounding box of the tail
 fn parse_bounding_box(instructions: &str) -> (Vector2<i32>, Vector2<i32>) {
     let mut pos = Vector2::new(0,0);
-    let mut pmin = pos.clone();
-    let mut pmax = pos.clone();
+    let mut pmin = pos;
+    let mut pmax = pos;
     for instr in instructions.lines() {
         let (n,dir) = parse_instruction(instr);
         pos += n * dir;
@@ -41,7 +41,7 @@ fn compute_tail_movement(phead: &Vector2<i32>, ptail: &Vector2<i32>) -> Vector2<
     let amplitude = dir_vec.amax();
     assert!(amplitude <= 2, "Direction vector too large??!");
     if amplitude == 2 {
-        dir_vec.apply(|x| if *x != 0 {*x = *x / x.abs()});
+        dir_vec.apply(|x| if *x != 0 {*x /= x.abs()});
     } else {
         dir_vec.assign_elem(Z);
     }
@@ -57,7 +57,7 @@ fn apply_instruction(
     let (n,dir) = parse_instruction(instr);
     for _ in 0..n {
         phead.add_assign(dir);
-        ptail.add_assign(compute_tail_movement(&phead, &ptail));
+        ptail.add_assign(compute_tail_movement(phead, ptail));
         history_ptail[(ptail[0] as usize, ptail[1] as usize)] += 1;
     }
 }
@@ -66,11 +66,11 @@ fn get_tail_positions(instructions: &str) -> DMatrix<i32> {
     let (pmin, pmax) = parse_bounding_box(instructions);
 
     let initial_pos = -pmin;
-    let mut phead = initial_pos.clone();
-    let mut ptail = initial_pos.clone();
+    let mut phead = initial_pos;
+    let mut ptail = initial_pos;
     
     let box_size = pmax.add_scalar(1).sub(pmin).map(|x| x as usize);
-    println!("Size of the bounding box: {} by {}", box_size[0], box_size[1]);
+    // println!("Size of the bounding box: {} by {}", box_size[0], box_size[1]);
     let mut history_ptail = DMatrix::from_element(box_size[0], box_size[1], 0);
 
     for instr in instructions.lines() {
@@ -85,10 +85,10 @@ fn get_whiptail_positions(instructions: &str) -> DMatrix<i32> {
     let length_tail: usize = 10;
     let (pmin, pmax) = parse_bounding_box(instructions);
     let initial_pos = -pmin;
-    let mut all_pos: [Vector2<i32>; 10] = [initial_pos.clone(); 10];
+    let mut all_pos: [Vector2<i32>; 10] = [initial_pos; 10];
     
     let box_size = pmax.add_scalar(1).sub(pmin).map(|x| x as usize);
-    println!("Size of the bounding box: {} by {}", box_size[0], box_size[1]);
+    // println!("Size of the bounding box: {} by {}", box_size[0], box_size[1]);
     let mut history_ptail = DMatrix::from_element(box_size[0], box_size[1], 0);
 
     for instr in instructions.lines() {
@@ -107,19 +107,22 @@ fn get_whiptail_positions(instructions: &str) -> DMatrix<i32> {
 }
 
 pub fn main() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
-    let history_ptail = get_tail_positions(&contents);
+pub fn solve(contents: &Input) -> u32 {
+    let history_ptail = get_tail_positions(contents);
 
     return history_ptail.map(|x| (x > 0) as u32).sum();
 }
 
 pub fn main_bonus() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> u32 {
     
-    let history_ptail = get_whiptail_positions(&contents);
+    let history_ptail = get_whiptail_positions(contents);
     // let display_history = history_ptail.map(|x| if x == 0 {"."} else {"#"});
     // println!("{}", display_history);
     return history_ptail.map(|x| (x > 0) as u32).sum();
@@ -1,27 +1,30 @@
-use std::fs;
+use crate::input::{Input, ReaderLines};
+use itertools::process_results;
+use std::io::{self, BufRead, Write};
+use std::iter;
 const INPUT_FILE: &str = "data/input10.txt";
 
 const LENGTH_DISPLAY: i32 = 40;
 
-fn get_increments(instructions: &str) -> Vec<i32> {
-    let mut add = Vec::new();
-    for inst in instructions.trim().lines() {
-        add.push(0);
-        if inst[..4].eq("addx") {
-            add.push(inst.split(" ").last().unwrap().parse().unwrap());
-        }
-    }
-    return add;
+/// Increments of the register at each cycle of an instruction: `noop` takes a cycle
+/// and `addx` two, with the addition happening at the end of the second one.
+fn instruction_increments(inst: &str) -> impl Iterator<Item = i32> {
+    let addition = if inst[..4].eq("addx") {
+        Some(inst.split(" ").last().unwrap().parse().unwrap())
+    } else {
+        None
+    };
+    return iter::once(0).chain(addition);
 }
 
-pub fn main() -> i32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+fn get_increments(instructions: &str) -> Vec<i32> {
+    return instructions.lines().flat_map(instruction_increments).collect();
+}
 
-    let increments = get_increments(&contents);
+fn sum_of_signal_strengths(increments: impl Iterator<Item = i32>) -> i32 {
     let mut sum_of_signal_strengths = 0;
     let mut x = 1;
-    for (i,&n) in increments.iter().enumerate() {
+    for (i,n) in increments.enumerate() {
         // Monitor the cycle
         if (i as i32 + 1) % LENGTH_DISPLAY == 20 {
             let signal_strength: i32 = ((i+1) as i32) * x;
@@ -30,28 +33,76 @@ pub fn main() -> i32 {
         x += n;
     }
     // println!("{x}");
-    
+
     return sum_of_signal_strengths;
 }
 
-pub fn main_bonus() -> String {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-
-    let increments = get_increments(&contents);
-
-    let mut crt = vec!["\n"];
+/// Draw the CRT, writing each row as soon as it is complete so that only one row is kept
+fn write_crt<W: Write>(increments: impl Iterator<Item = i32>, out: &mut W) -> io::Result<()> {
+    let mut row = String::from("\n");
     let mut x: i32 = 1;
-    for (i,&n) in increments.iter().enumerate() {
+    for (i,n) in increments.enumerate() {
         let pixel_position = (i as i32) % LENGTH_DISPLAY;
         if x.abs_diff(pixel_position) <= 1 {
-            crt.push("⠿");
+            row.push('⠿');
         } else {
-            crt.push(" ");
+            row.push(' ');
+        }
+        if pixel_position + 1 == LENGTH_DISPLAY {
+            row.push('\n');
+            out.write_all(row.as_bytes())?;
+            row.clear();
         }
-        if pixel_position + 1 == LENGTH_DISPLAY { crt.push("\n"); }
         x += n;
     }
+    return out.write_all(row.as_bytes());
+}
+
+fn draw_crt(increments: impl Iterator<Item = i32>) -> String {
+    let mut crt = Vec::new();
+    write_crt(increments, &mut crt).expect("Writing to memory does not fail");
+    return String::from_utf8(crt).unwrap();
+}
+
+/// Apply `f` to the increments of the instructions of a reader, read one line at a time
+fn with_reader_increments<R, T, F>(reader: R, f: F) -> io::Result<T>
+    where R: BufRead, F: FnOnce(&mut dyn Iterator<Item = i32>) -> T
+{
+    return process_results(ReaderLines::new(reader), |lines| {
+        let mut increments = lines
+            .filter(|l| !l.is_empty())
+            .flat_map(|l| instruction_increments(&l));
+        f(&mut increments)
+    });
+}
 
-    return String::from(crt.join(""));
-}
\ No newline at end of file
+pub fn main() -> i32 {
+    return solve(&Input::read(INPUT_FILE));
+}
+
+pub fn solve(contents: &Input) -> i32 {
+    return sum_of_signal_strengths(get_increments(contents).into_iter());
+}
+
+pub fn solve_reader<R: BufRead>(reader: R) -> io::Result<i32> {
+    return with_reader_increments(reader, |increments| sum_of_signal_strengths(increments));
+}
+
+pub fn main_bonus() -> String {
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> String {
+    return draw_crt(get_increments(contents).into_iter());
+}
+
+/// The drawing is returned whole, so it grows with the input: see `draw_reader` to write it
+/// row by row instead.
+pub fn solve_bonus_reader<R: BufRead>(reader: R) -> io::Result<String> {
+    return with_reader_increments(reader, |increments| draw_crt(increments));
+}
+
+/// Draw the CRT of the instructions of a reader, writing each row as soon as it is complete
+pub fn draw_reader<R: BufRead, W: Write>(reader: R, out: &mut W) -> io::Result<()> {
+    return with_reader_increments(reader, |increments| write_crt(increments, out))?;
+}
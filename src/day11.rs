@@ -1,4 +1,4 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input11.txt";
 
 use std::collections::VecDeque;
@@ -65,7 +65,7 @@ struct Monkey {
 
 impl Monkey {
     /// Take an argument of the form
-    /// ```
+    /// ```text
     /// Monkey 0:
     ///   Starting items: 79, 98
     ///   Operation: new = old * 19
@@ -117,10 +117,11 @@ impl Monkey {
 }
 
 pub fn main() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
-    let contents = contents.split("\n\n");
+pub fn solve(contents: &Input) -> u32 {
+    let contents = contents.blocks();
     let mut item_pile: Vec<Item> = Vec::new();
     let mut monkeys: Vec<Monkey> = Vec::new();
 
@@ -160,14 +161,15 @@ pub fn main() -> u32 {
     tmp_activity.sort();
     tmp_activity.reverse();
 
-    return tmp_activity[..2].into_iter().product();
+    return tmp_activity[..2].iter().product();
 }
 
 pub fn main_bonus() -> u64 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
 
-    let contents = contents.split("\n\n");
+pub fn solve_bonus(contents: &Input) -> u64 {
+    let contents = contents.blocks();
     let mut item_pile: Vec<Item> = Vec::new();
     let mut monkeys: Vec<Monkey> = Vec::new();
 
@@ -214,7 +216,7 @@ pub fn main_bonus() -> u64 {
     //     println!("Monkey {}: {}", m_idx, monkey.items.iter().map(|&i| item_pile[i].worry_level.to_string()).join(", "));
     // }
 
-    let mut tmp_activity = activity.iter().map(|x| *x as u32).collect_vec();
+    let mut tmp_activity = activity.iter().copied().collect_vec();
     tmp_activity.sort();
     tmp_activity.reverse();
 
@@ -1,11 +1,12 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input12.txt";
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use itertools::Itertools;
 use nalgebra::DMatrix;
 use petgraph::graphmap::DiGraphMap;
 use petgraph::algo::dijkstra;
+use petgraph::Direction;
 // use petgraph::dot::{Dot, Config};
 use std::cmp::{max,min};
 
@@ -42,20 +43,67 @@ fn compute_graph_edges(altitudes: &DMatrix<u32>) -> HashSet<(MatIndex,MatIndex)>
         let l = (i, max(1,j)-1);
         let r = (i, min(ncols-1,j+1));
         for n in [u,d,l,r] {
-            add_edge_if_valid((i,j), n, &altitudes, &mut graph_edges);
+            add_edge_if_valid((i,j), n, altitudes, &mut graph_edges);
         }
     }
     return graph_edges;
 }
 
-pub fn main() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-
+fn parse_map(contents: &str) -> DMatrix<char> {
     let data = contents.lines().map(|l| l.trim().chars().collect_vec()).collect_vec();
-
     let (nrows, ncols) = (data.len(), data[0].len());
-    let data = DMatrix::from_fn(nrows, ncols, |i,j| data[i][j]);
+    return DMatrix::from_fn(nrows, ncols, |i,j| data[i][j]);
+}
+
+fn find_position(data: &DMatrix<char>, c: char) -> MatIndex {
+    let (nrows, ncols) = data.shape();
+    return (0..nrows).cartesian_product(0..ncols)
+        .find(|(i,j)| data[(*i,*j)] == c).unwrap();
+}
+
+/// Walk back from the end along decreasing distances to recover a shortest path
+fn shortest_path(
+    g: &DiGraphMap<MatIndex, ()>, 
+    node_map: &HashMap<MatIndex, u32>, 
+    end_node: MatIndex) -> Vec<MatIndex> 
+{
+    let mut path = vec![end_node];
+    let mut node = end_node;
+    while node_map[&node] > 0 {
+        let previous_distance = node_map[&node] - 1;
+        node = g.neighbors_directed(node, Direction::Incoming)
+            .find(|n| node_map.get(n) == Some(&previous_distance))
+            .unwrap();
+        path.push(node);
+    }
+    path.reverse();
+    return path;
+}
+
+fn map_with_path_to_string(data: &DMatrix<char>, path: &[MatIndex]) -> String {
+    let mut drawing = data.clone();
+    for (&(i1,j1), &(i2,j2)) in path.iter().zip(path[1..].iter()) {
+        drawing[(i1,j1)] = match (i2 as i64 - i1 as i64, j2 as i64 - j1 as i64) {
+            (-1, _) => '^',
+            ( 1, _) => 'v',
+            (_, -1) => '<',
+            (_,  _) => '>',
+        };
+    }
+    let mut out = String::new();
+    for row in drawing.row_iter() {
+        out.extend(row.iter());
+        out.push('\n');
+    }
+    return out;
+}
+
+pub fn main() -> u32 {
+    return solve(&Input::read(INPUT_FILE));
+}
+
+pub fn solve(contents: &Input) -> u32 {
+    let data = parse_map(contents);
     let altitudes = data.map(char_to_altitude);
 
     // println!("{} becomes {}", data, altitudes);
@@ -63,31 +111,43 @@ pub fn main() -> u32 {
     let graph_edges = compute_graph_edges(&altitudes);
     let g = DiGraphMap::<MatIndex, ()>::from_edges(&graph_edges);
 
-    let start_node = (0..nrows).cartesian_product(0..ncols)
-        .find(|(i,j)| data[(*i,*j)] == 'S').unwrap();
-    let end_node = (0..nrows).cartesian_product(0..ncols)
-        .find(|(i,j)| data[(*i,*j)] == 'E').unwrap();
+    let start_node = find_position(&data, 'S');
+    let end_node = find_position(&data, 'E');
     let node_map = dijkstra(&g, start_node, Some(end_node), |_| 1);
 
     return node_map[&end_node];
 }
 
-pub fn main_bonus() -> u32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+/// Draw the heightmap with arrows along a shortest path from the start to the end
+pub fn render(contents: &Input) -> String {
 
-    let data = contents.lines().map(|l| l.trim().chars().collect_vec()).collect_vec();
-    let (nrows, ncols) = (data.len(), data[0].len());
+    let data = parse_map(contents);
+    let altitudes = data.map(char_to_altitude);
+    let graph_edges = compute_graph_edges(&altitudes);
+    let g = DiGraphMap::<MatIndex, ()>::from_edges(&graph_edges);
 
-    let data = DMatrix::from_fn(nrows, ncols, |i,j| data[i][j]);
+    let end_node = find_position(&data, 'E');
+    let node_map = dijkstra(&g, find_position(&data, 'S'), Some(end_node), |_| 1);
+    let path = shortest_path(&g, &node_map, end_node);
+
+    return map_with_path_to_string(&data, &path);
+}
+
+pub fn main_bonus() -> u32 {
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> u32 {
+    let data = parse_map(contents);
+    let (nrows, ncols) = data.shape();
     let altitudes = data.map(char_to_altitude);
-        
+
     let graph_edges = compute_graph_edges(&altitudes);
     // Reverse edges (we're interested in travels "from" the end point in Dijkstra's algorithm)
     let graph_edges = graph_edges.iter().map(|&(i1,i2)| (i2,i1));
     let g = DiGraphMap::<MatIndex, ()>::from_edges(graph_edges);
 
-    let start_node = (0..nrows).cartesian_product(0..ncols).find(|(i,j)| data[(*i,*j)] == 'E').unwrap();
+    let start_node = find_position(&data, 'E');
     let node_map = dijkstra(&g, start_node, None, |_| 1);
 
     let shortest_hike = (0..nrows).cartesian_product(0..ncols)
@@ -1,4 +1,4 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input13.txt";
 
 use itertools::Itertools;
@@ -37,7 +37,7 @@ impl<T> cmp::Ord for NestedList<T>
     where T: Ord + Copy 
 {
     fn cmp(&self, other: &Self) -> Ordering {
-        println!("using custom comparator");
+        // println!("using custom comparator");
         match (self, other) {
             (Self::Elem(n), Self::Elem(m)) => n.cmp(m),
             (Self::List(u), Self::List(v)) => u.cmp(v),
@@ -89,7 +89,7 @@ fn take_until_unbalanced(
 
 
 /// Transforms s string list into a nested list, e.g.
-/// ```rust
+/// ```text
 /// assert_eq!(convert_to_list("[1,2,3]"), List([Elem(1), Elem(2), Elem(3)]));
 /// assert_eq!(convert_to_list("[[1],[2]]"), List([List([Elem(1)]), List([Elem(2)])]));
 /// assert_eq!(convert_to_list("[[]]"), List([List([])]));
@@ -113,9 +113,11 @@ fn convert_to_list(s: &str) -> NestedList<i32> {
 }
 
 pub fn main() -> usize {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-    let contents = contents.trim().split("\n\n");
+    return solve(&Input::read(INPUT_FILE));
+}
+
+pub fn solve(contents: &Input) -> usize {
+    let contents = contents.blocks();
 
     let mut lhs = Vec::new();
     let mut rhs = Vec::new();
@@ -135,11 +137,12 @@ pub fn main() -> usize {
 }
 
 pub fn main_bonus() -> usize {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
 
-    let mut lists = contents.split("\n\n")
-        .map(|s| s.lines()).flatten()
+pub fn solve_bonus(contents: &Input) -> usize {
+    let mut lists = contents.blocks()
+        .flat_map(|s| s.lines())
         .map(convert_to_list)
         .collect_vec();
 
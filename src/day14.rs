@@ -1,4 +1,5 @@
-use std::{fs, cmp::{max,min}};
+use std::cmp::{max,min};
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input14.txt";
 
 use itertools::Itertools;
@@ -50,10 +51,9 @@ fn normalize_paths(paths: &mut Vec<Path>) -> MatIndex {
     return (SOURCE_POSITION.1, SOURCE_POSITION.0 - x_offset);
 }
 
-fn domain_size(paths: &Vec<Path>) -> (usize,usize) {
+fn domain_size(paths: &[Path]) -> (usize,usize) {
     return paths.iter()
-        .map(|p| p.iter())
-        .flatten()
+        .flat_map(|p| p.iter())
         .fold((0,0), |(ymax,xmax),&(y,x)| (max(ymax,y+LOWER_BUFFER), max(xmax,x+RIGHT_BUFFER)));
 }
 
@@ -71,7 +71,7 @@ fn obstacle_matrix(paths: &Vec<Path>) -> DMatrix<bool> {
     let (ymax,xmax) = domain_size(paths);
     let mut mat = DMatrix::from_element(ymax+1, xmax+1, false);
     for p in paths {
-        fill_rock_path(&mut mat, &p);
+        fill_rock_path(&mut mat, p);
     }
     return mat;
 }
@@ -80,17 +80,16 @@ fn next_sand_position(occupancy: &DMatrix<bool>, (y,x): MatIndex) -> MatIndex {
     return [(y+1,x), (y+1,x-1), (y+1,x+1), (y,x)].into_iter().find(|&idx| !occupancy[idx]).unwrap();
 }
 
-pub fn main() -> usize {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-
+/// Pour sand until it flows into the abyss.
+/// Returns the rocks, the rocks and sand at rest, and the number of grains at rest.
+fn pour_into_abyss(contents: &str) -> (DMatrix<bool>, DMatrix<bool>, usize) {
     let mut paths = contents.lines().map(parse_path).collect_vec();
     let source_position = normalize_paths(&mut paths);
 
     let obstacle_matrix = obstacle_matrix(&paths);
     let domain_length = obstacle_matrix.shape().1;
     let infinite_depth = obstacle_matrix.shape().0 - LOWER_BUFFER;
-    println!("Infinite Depth: {}", infinite_depth);
+    // println!("Infinite Depth: {}", infinite_depth);
     // println!("{}", obstacle_matrix.map(|b| if b {"#"} else {"."}));
     let mut occupancy = obstacle_matrix.clone();
 
@@ -108,16 +107,42 @@ pub fn main() -> usize {
             next_position => next_position,
         };
     }
-    // println!("{}", occupancy.zip_map(&obstacle_matrix, |is_occupied,is_rock| if is_rock {"#"} else if is_occupied {"o"} else {"."}));
+    return (obstacle_matrix, occupancy, number_of_grains);
+}
+
+fn cave_to_string(obstacle_matrix: &DMatrix<bool>, occupancy: &DMatrix<bool>) -> String {
+    let mut out = String::new();
+    for (rocks, occupied) in obstacle_matrix.row_iter().zip(occupancy.row_iter()) {
+        for (&is_rock, &is_occupied) in rocks.iter().zip(occupied.iter()) {
+            out.push(if is_rock {'#'} else if is_occupied {'o'} else {'.'});
+        }
+        out.push('\n');
+    }
+    return out;
+}
+
+pub fn main() -> usize {
+    return solve(&Input::read(INPUT_FILE));
+}
+
+pub fn solve(contents: &Input) -> usize {
+    let (_, _, number_of_grains) = pour_into_abyss(contents);
     return number_of_grains;
 }
 
+/// Draw the cave once sand flows into the abyss, with `#` for rock and `o` for sand
+pub fn render(contents: &Input) -> String {
+    let (obstacle_matrix, occupancy, _) = pour_into_abyss(contents);
+    return cave_to_string(&obstacle_matrix, &occupancy);
+}
+
 pub fn main_bonus() -> usize {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
 
+pub fn solve_bonus(contents: &Input) -> usize {
     let mut paths = contents.lines().map(parse_path).collect_vec();
-    let max_depth = paths.iter().map(|p| p.iter()).flatten().map(|&(_,y)| y).max().unwrap();
+    let max_depth = paths.iter().flat_map(|p| p.iter()).map(|&(_,y)| y).max().unwrap();
     paths.push(vec![
         (SOURCE_POSITION.0 - max_depth - INFINITE_FLOOR_BUFFER, max_depth + 2), 
         (SOURCE_POSITION.0 + max_depth + INFINITE_FLOOR_BUFFER, max_depth + 2)
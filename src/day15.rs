@@ -1,4 +1,4 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input15.txt";
 
 const X_MAX: i32 = 4000000;
@@ -20,8 +20,8 @@ use nom::{self,
 };
 
 fn str_to_i32(input: &str) -> IResult<&str, i32> {
-    if input.chars().next() == Some('-') {
-        return map(|s| str_to_i32(s), |x| -x)(&input[1..]);
+    if let Some(input) = input.strip_prefix('-') {
+        return map(str_to_i32, |x| -x)(input);
     }
     return map_res(digit1, str::parse::<i32>)(input);
 }
@@ -89,7 +89,7 @@ impl DisjointUnion {
         }
     }
 
-    fn iter(&self) -> IterDisjointUnion {
+    fn iter(&self) -> IterDisjointUnion<'_> {
         IterDisjointUnion { at: Some(self) }
     }
 }
@@ -234,10 +234,11 @@ fn clockwise_increment(p: Position) -> Position {
 }
 
 pub fn main() -> i32 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
-    let sensors: Vec<Sensor> = contents.lines().map(|l| Sensor::new(l)).collect();
+pub fn solve(contents: &Input) -> i32 {
+    let sensors: Vec<Sensor> = contents.lines().map(Sensor::new).collect();
     let ranges_on_line = sensors.iter()
         .map(|s| s.range_on_yline(Y_LINE))
         .fold(DisjointUnion::new(), 
@@ -258,10 +259,12 @@ pub fn main() -> i32 {
 }
 
 pub fn main_bonus() -> u64 {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
-    let sensors = contents.lines().map(|l| Sensor::new(l)).collect_vec();
-    let beacon_position = sensors.iter().map(|s| s.periphery()).flatten()
+    return solve_bonus(&Input::read(INPUT_FILE));
+}
+
+pub fn solve_bonus(contents: &Input) -> u64 {
+    let sensors = contents.lines().map(Sensor::new).collect_vec();
+    let beacon_position = sensors.iter().flat_map(|s| s.periphery())
         .filter(|p| p.x >= 0 && p.y >= 0 && p.x <= X_MAX && p.y <= Y_MAX)
         .find_or_first(|p| sensors.iter().all(|s| p.distance1(&s.position) > s.range))
         .unwrap();
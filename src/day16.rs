@@ -1,12 +1,10 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/test17.txt";
 
 pub fn main() {
-    let _contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    let _contents = Input::read(INPUT_FILE);
 }
 
 pub fn main_bonus() {
-    let _contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    let _contents = Input::read(INPUT_FILE);
 }
@@ -1,9 +1,9 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/test17.txt";
 
 use std::iter::Cycle;
 use std::collections::VecDeque;
-use std::cmp::max;
+use std::fmt;
 
 use core::slice::Iter;
 use itertools::Itertools;
@@ -62,7 +62,10 @@ impl Shape {
             self.0 >>= 1; 
         }
     }
-    fn to_string(&self) -> String {
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let data = self.0;
         let mut out = String::new();
         for height in (0..4).rev() {
@@ -72,21 +75,21 @@ impl Shape {
             }
             if height != 0 {out.push('\n');}
         }
-        return out;
+        return write!(f, "{}", out);
     }
 }
 
 const ROW_SIZE: u32 = 8;
 /// Floor 
 const MASK: u32 = 0b1111111;
-/// ```
+/// ```text
 /// 0b01000000 i.e. .#......
 ///   01000000 i.e. .#......
 ///   01000000 i.e. .#......
 ///   01000000 i.e. .#......
 /// ```
 const LEFT_WALL: u32 = 0x40404040;
-/// ```
+/// ```text
 /// 0b01000000 i.e. .......#
 ///   01000000 i.e. .......#
 ///   01000000 i.e. .......#
@@ -186,11 +189,13 @@ fn drop_inaccessible(rock_formation: &mut VecDeque<u32>) -> usize {
     // }
     return number_of_removed;
 }
-fn rock_pile_height(
-    shapes: &Vec<Shape>,
-    directions: &Vec<LeftOrRight>,
+/// Let the rocks fall, returning the part of the rock formation which is still accessible
+/// (from top to floor) and the height of the part which was dropped below it.
+fn rock_formation(
+    shapes: &[Shape],
+    directions: &[LeftOrRight],
     number_of_falls: u64,
-) -> usize {
+) -> (VecDeque<u32>, usize) {
     let mut shapes = shapes.iter().cycle();
     let mut directions = directions.iter().cycle();
     let mut rock_formation = VecDeque::new();
@@ -198,51 +203,74 @@ fn rock_pile_height(
     rock_formation.push_back(MASK);
 
     let mut pile_height = 0;
-    let mut max_memory_length = 0;
     for _ in 0..number_of_falls {
-        let mut shape = shapes.next().unwrap().clone();
+        let mut shape = *shapes.next().unwrap();
         initial_fall(&mut shape, &mut directions);
-        let depth = fall_until_stagnant(&mut shape, &mut directions, &mut rock_formation);
+        let depth = fall_until_stagnant(&mut shape, &mut directions, &rock_formation);
         add_shape_to_rock_formation(&mut rock_formation, &mut shape, depth);
-        max_memory_length = max(max_memory_length, rock_formation.len());
         // update_accessibility(&mut rock_formation);
         pile_height += drop_inaccessible(&mut rock_formation);
     }
-    println!("{}", max_memory_length);
-
-    // // Display
-    // let mut out = String::new();
-    // for row in &rock_formation {
-    //     for offset in (0..7).rev() {
-    //         out.push(if (row >> offset) & 1 != 0 {'#'} else {'.'});
-    //     }
-    //     out.push('\n');
-    // }
-    // println!("{}", out);
 
+    return (rock_formation, pile_height);
+}
 
-    pile_height += rock_formation.len() - 1;
-    return pile_height;
+fn rock_pile_height(
+    shapes: &[Shape],
+    directions: &[LeftOrRight],
+    number_of_falls: u64,
+) -> usize {
+    let (rock_formation, dropped_height) = rock_formation(shapes, directions, number_of_falls);
+    return dropped_height + rock_formation.len() - 1;
+}
+
+fn rock_formation_to_string(rock_formation: &VecDeque<u32>, dropped_height: usize) -> String {
+    // The last row is either the floor or a full row of rocks
+    let number_of_rows = if dropped_height == 0 {rock_formation.len() - 1} else {rock_formation.len()};
+    let mut out = String::new();
+    for row in rock_formation.iter().take(number_of_rows) {
+        out.push('|');
+        for offset in (0..7).rev() {
+            out.push(if (row >> offset) & 1 != 0 {'#'} else {'.'});
+        }
+        out.push_str("|\n");
+    }
+    if dropped_height == 0 {
+        out.push_str("+-------+\n");
+    } else {
+        out.push_str(&format!("|~~~~~~~| {} more rows\n", dropped_height - 1));
+    }
+    return out;
 }
 
 
 
 pub fn main() -> usize {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
-    let jet_directions = contents.trim().chars()
+pub fn solve(contents: &Input) -> usize {
+    let jet_directions = contents.chars()
         .map(LeftOrRight::from)
         .collect_vec(); // hopefully this works like shadowing
     // let mut jet_directions = jet_directions.iter().cycle();
     // let mut shapes = FALLING_SHAPES.iter().cycle();
 
-    let res = rock_pile_height(&FALLING_SHAPES.to_vec(), &jet_directions, NUMBER_OF_ROCKS);
+    let res = rock_pile_height(&FALLING_SHAPES, &jet_directions, NUMBER_OF_ROCKS);
 
     return res;
 }
 
+/// Draw the top of the tower, down to the last row which rocks cannot go through
+pub fn render(contents: &Input) -> String {
+    let jet_directions = contents.chars()
+        .map(LeftOrRight::from)
+        .collect_vec();
+
+    let (rock_formation, dropped_height) = rock_formation(&FALLING_SHAPES, &jet_directions, NUMBER_OF_ROCKS);
+    return rock_formation_to_string(&rock_formation, dropped_height);
+}
+
 pub fn main_bonus() {
-    let _contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    let _contents = Input::read(INPUT_FILE);
 }
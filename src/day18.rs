@@ -1,4 +1,4 @@
-use std::{fs, time};
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input18.txt";
 
 // use std::ops::Add;
@@ -82,9 +82,10 @@ impl Cube {
 // }
 
 pub fn main() -> usize {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
+pub fn solve(contents: &Input) -> usize {
     // let start = time::Instant::now();
 
     let mut cubes: Vec<Cube> = contents.lines().map(Cube::from).collect();
@@ -96,8 +97,8 @@ pub fn main() -> usize {
         //     .collect();
         let neighbours: Vec<Position> = c1.face_visible.keys().cloned().collect();
         for c2 in cright.iter_mut().filter(|c| neighbours.contains(&c.pos)) {
-            c1.face_visible.get_mut(&c2.pos).map(|v| *v = false);
-            c2.face_visible.get_mut(&c1.pos).map(|v| *v = false);
+            if let Some(v) = c1.face_visible.get_mut(&c2.pos) { *v = false; }
+            if let Some(v) = c2.face_visible.get_mut(&c1.pos) { *v = false; }
             // if let Some(v) = c1.face_visible.get_mut(&c2.pos) {
             //     *v = false;
             // }
@@ -111,9 +112,8 @@ pub fn main() -> usize {
 }
 
 pub fn main_bonus() {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    let _contents = Input::read(INPUT_FILE);
 
     let x: [i32;3] = [1,2,3];
-    let y = x.map(|u| u+1);
+    let _y = x.map(|u| u+1);
 }
@@ -1,4 +1,4 @@
-use std::fs;
+use crate::input::Input;
 const INPUT_FILE: &str = "data/input25.txt";
 
 use std::str::Chars;
@@ -23,7 +23,7 @@ impl Snafu {
     fn from(s: &str) -> Self {
         Snafu(String::from(s))
     }
-    fn iter(&self) -> IterSnafu {
+    fn iter(&self) -> IterSnafu<'_> {
         IterSnafu(self.0.chars().rev())
     }
 }
@@ -42,7 +42,7 @@ impl<'a> Iterator for IterSnafu<'a> {
         self.0.next()
     }
 }
-fn aux_add_snafu<'a>(lhs: &mut IterSnafu, rhs: &mut IterSnafu, offset: i32) -> String {
+fn aux_add_snafu(lhs: &mut IterSnafu, rhs: &mut IterSnafu, offset: i32) -> String {
     let (s1, s2) = (lhs.next(), rhs.next());
     if (s1,s2) == (None,None) {
         return String::from(SNAFU[(offset+2) as usize]);
@@ -60,9 +60,10 @@ fn aux_add_snafu<'a>(lhs: &mut IterSnafu, rhs: &mut IterSnafu, offset: i32) -> S
 }
 
 pub fn main() -> String {
-    let contents = fs::read_to_string(INPUT_FILE)
-        .expect("Should have been able to read the file.");
+    return solve(&Input::read(INPUT_FILE));
+}
 
+pub fn solve(contents: &Input) -> String {
     let res = contents.lines().map(Snafu::from).fold(Snafu::new(),|a,b| a+b);
     return res.0;
 }
@@ -0,0 +1,45 @@
+//! C interface to the solvers, so that they can be called from other languages (e.g. Julia's
+//! `ccall` or Python's `ctypes`) without going through the binary. The header is generated
+//! into `include/aoc22.h` when building.
+
+use std::ffi::{c_char, CString};
+use std::{panic, slice, str};
+
+use crate::input::Input;
+use crate::solutions;
+
+/// Solve a part (1 or 2) of a day on the `len` bytes of UTF-8 text pointed to by `input`
+/// (which does not need to be NUL-terminated).
+///
+/// Returns the answer as a NUL-terminated string, to be released with `aoc22_free_answer`,
+/// or NULL if that part is not solved, the input is not valid UTF-8 or the solver failed.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, or be NULL with `len == 0`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc22_solve(day: u32, part: u32, input: *const u8, len: usize) -> *mut c_char {
+    let bytes = if input.is_null() { &[] } else { slice::from_raw_parts(input, len) };
+    let answer = str::from_utf8(bytes).ok()
+        .zip(solutions::solver(day, part))
+        // Panics must not unwind into the caller
+        .and_then(|(text, solver)| panic::catch_unwind(|| solver(&Input::normalize(text))).ok())
+        .and_then(|answer| CString::new(answer).ok());
+
+    return match answer {
+        Some(answer) => answer.into_raw(),
+        None => std::ptr::null_mut(),
+    };
+}
+
+/// Release an answer returned by `aoc22_solve`. Does nothing on NULL.
+///
+/// # Safety
+///
+/// `answer` must come from `aoc22_solve` and not have been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc22_free_answer(answer: *mut c_char) {
+    if !answer.is_null() {
+        drop(CString::from_raw(answer));
+    }
+}
@@ -0,0 +1,116 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::iter::Sum;
+use std::ops::Deref;
+use std::str::Lines;
+
+use crate::aoc::DATA_DIR;
+
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Name of the file holding the puzzle input of a day
+pub fn file_name(day: u32) -> String {
+    return format!("input{:02}.txt", day);
+}
+
+/// Puzzle input in a canonical form, so that every day parses the same text whatever the
+/// editor or OS it went through: `\n` line endings, no byte order mark, no trailing
+/// whitespace at the end of lines, and no trailing blank lines (nor final newline).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Input(String);
+
+impl Input {
+    pub fn read(path: &str) -> Self {
+        let raw = fs::read_to_string(path)
+            .expect("Should have been able to read the file.");
+        return Self::normalize(&raw);
+    }
+
+    /// Read the puzzle input of a day, e.g. `data/input05.txt`
+    pub fn read_day(day: u32) -> Self {
+        return Self::read(&format!("{}/{}", DATA_DIR, file_name(day)));
+    }
+
+    pub fn normalize(raw: &str) -> Self {
+        let raw = raw.strip_prefix(BYTE_ORDER_MARK).unwrap_or(raw);
+        let mut text = String::with_capacity(raw.len());
+        // `lines` already drops the '\r' of "\r\n" endings
+        for line in raw.lines() {
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+        text.truncate(text.trim_end().len());
+        return Input(text);
+    }
+
+    pub fn text(&self) -> &str {
+        &self.0
+    }
+
+    pub fn lines(&self) -> Lines<'_> {
+        self.0.lines()
+    }
+
+    /// Groups of lines separated by (one or more) blank lines
+    pub fn blocks(&self) -> impl Iterator<Item = &str> + Clone {
+        self.0.split("\n\n")
+            .map(|block| block.trim_start_matches('\n'))
+            .filter(|block| !block.is_empty())
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+pub fn open(path: &str) -> BufReader<File> {
+    let file = File::open(path).expect("Should have been able to open the file.");
+    return BufReader::new(file);
+}
+
+/// Lines of a reader, one at a time, normalized like `Input`: no byte order mark, no line
+/// ending and no trailing whitespace. Trailing blank lines are kept, since they can only be
+/// told apart once the end is reached.
+pub struct ReaderLines<R: BufRead> {
+    reader: R,
+    is_first: bool,
+}
+
+impl<R: BufRead> ReaderLines<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader, is_first: true }
+    }
+}
+
+impl<R: BufRead> Iterator for ReaderLines<R> {
+    type Item = io::Result<String>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => return None,
+            Ok(_) => (),
+            Err(err) => return Some(Err(err)),
+        }
+        if self.is_first {
+            self.is_first = false;
+            if line.starts_with(BYTE_ORDER_MARK) {
+                line.remove(0);
+            }
+        }
+        line.truncate(line.trim_end().len());
+        return Some(Ok(line));
+    }
+}
+
+/// Apply a function to every non-blank line of a reader and sum the results, in constant memory
+pub fn sum_over_lines<R, T, F>(reader: R, f: F) -> io::Result<T>
+    where R: BufRead, T: Sum<T>, F: Fn(&str) -> T
+{
+    return ReaderLines::new(reader)
+        .filter(|line| !matches!(line, Ok(l) if l.is_empty()))
+        .map(|line| line.map(|l| f(&l)))
+        .sum();
+}
@@ -0,0 +1,204 @@
+use std::cmp::{max, min};
+use std::fmt;
+use std::str::FromStr;
+
+/// Closed interval of integers, i.e. both bounds are included. It is never empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    start: i32,
+    end: i32,
+}
+
+impl Interval {
+    /// None when `start > end`, as the interval would be empty
+    pub fn new(start: i32, end: i32) -> Option<Self> {
+        return (start <= end).then_some(Self { start, end });
+    }
+
+    pub fn point(x: i32) -> Self {
+        return Self { start: x, end: x };
+    }
+
+    pub fn start(self) -> i32 {
+        return self.start;
+    }
+
+    pub fn end(self) -> i32 {
+        return self.end;
+    }
+
+    /// Number of integers in the interval
+    #[allow(clippy::len_without_is_empty)] // It is never empty
+    pub fn len(self) -> u64 {
+        return self.end.abs_diff(self.start) as u64 + 1;
+    }
+
+    pub fn contains(self, x: i32) -> bool {
+        return self.start <= x && x <= self.end;
+    }
+
+    /// Whether `other` is inside this interval
+    pub fn covers(self, other: Interval) -> bool {
+        return self.start <= other.start && other.end <= self.end;
+    }
+
+    pub fn overlaps(self, other: Interval) -> bool {
+        return max(self.start, other.start) <= min(self.end, other.end);
+    }
+
+    pub fn intersection(self, other: Interval) -> Option<Interval> {
+        return Interval::new(max(self.start, other.start), min(self.end, other.end));
+    }
+
+    /// None when there is a gap between both intervals. Adjacent intervals, such as 1-2 and 3-4,
+    /// have a union since there is no integer between them.
+    pub fn union(self, other: Interval) -> Option<Interval> {
+        let (first, second) = if self.start <= other.start { (self, other) } else { (other, self) };
+        if (second.start as i64) > (first.end as i64) + 1 {
+            return None;
+        }
+        return Some(Interval { start: first.start, end: max(first.end, second.end) });
+    }
+
+    /// What remains of this interval without `other`: nothing, one interval, or two when
+    /// `other` is strictly inside
+    pub fn difference(self, other: Interval) -> Vec<Interval> {
+        if !self.overlaps(other) {
+            return vec![self];
+        }
+        let mut pieces = Vec::with_capacity(2);
+        if self.start < other.start {
+            pieces.push(Interval { start: self.start, end: other.start - 1 });
+        }
+        if other.end < self.end {
+            pieces.push(Interval { start: other.end + 1, end: self.end });
+        }
+        return pieces;
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseIntervalError {
+    /// Not of the form `start-end`
+    Format(String),
+    InvalidBound(String),
+    /// The start is after the end
+    Reversed(i32, i32),
+}
+
+impl fmt::Display for ParseIntervalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseIntervalError::Format(s) => write!(f, "'{}' is not of the form start-end", s),
+            ParseIntervalError::InvalidBound(s) => write!(f, "'{}' is not a valid bound", s),
+            ParseIntervalError::Reversed(start, end) => write!(f, "{} is after {}", start, end),
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = ParseIntervalError;
+
+    /// Parse `start-end`, where bounds may be negative, e.g. `-3--1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The dash separating the bounds is not the sign of the start
+        let dash = s.char_indices().skip(1).find(|&(_, c)| c == '-').map(|(i, _)| i)
+            .ok_or_else(|| ParseIntervalError::Format(String::from(s)))?;
+        let bound = |b: &str| b.trim().parse::<i32>().map_err(|_| ParseIntervalError::InvalidBound(String::from(b)));
+        let (start, end) = (bound(&s[..dash])?, bound(&s[dash + 1..])?);
+        return Interval::new(start, end).ok_or(ParseIntervalError::Reversed(start, end));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iv(start: i32, end: i32) -> Interval {
+        return Interval::new(start, end).unwrap();
+    }
+
+    #[test]
+    fn new_rejects_reversed_bounds() {
+        assert_eq!(Interval::new(3, 2), None);
+        assert_eq!(Interval::new(2, 2), Some(Interval::point(2)));
+    }
+
+    #[test]
+    fn len_counts_both_bounds() {
+        assert_eq!(iv(2, 4).len(), 3);
+        assert_eq!(Interval::point(7).len(), 1);
+        assert_eq!(iv(-2, 2).len(), 5);
+        assert_eq!(iv(i32::MIN, i32::MAX).len(), 1 << 32);
+    }
+
+    #[test]
+    fn contains_bounds() {
+        let i = iv(2, 4);
+        assert!(i.contains(2) && i.contains(3) && i.contains(4));
+        assert!(!i.contains(1) && !i.contains(5));
+    }
+
+    #[test]
+    fn covers() {
+        assert!(iv(2, 8).covers(iv(3, 7)));
+        assert!(iv(2, 8).covers(iv(2, 8)));
+        assert!(iv(2, 8).covers(Interval::point(8)));
+        assert!(!iv(3, 7).covers(iv(2, 8)));
+        assert!(!iv(2, 4).covers(iv(4, 6)));
+    }
+
+    #[test]
+    fn overlaps_on_a_single_section() {
+        assert!(iv(5, 7).overlaps(iv(7, 9)));
+        assert!(iv(7, 9).overlaps(iv(5, 7)));
+        assert!(iv(2, 8).overlaps(iv(3, 7)));
+        assert!(!iv(2, 4).overlaps(iv(5, 6)));
+        assert!(!iv(5, 6).overlaps(iv(2, 4)));
+    }
+
+    #[test]
+    fn intersection() {
+        assert_eq!(iv(5, 7).intersection(iv(7, 9)), Some(Interval::point(7)));
+        assert_eq!(iv(2, 8).intersection(iv(3, 7)), Some(iv(3, 7)));
+        assert_eq!(iv(2, 4).intersection(iv(5, 6)), None);
+    }
+
+    #[test]
+    fn union_of_overlapping_and_adjacent() {
+        assert_eq!(iv(2, 4).union(iv(3, 6)), Some(iv(2, 6)));
+        assert_eq!(iv(3, 6).union(iv(2, 4)), Some(iv(2, 6)));
+        assert_eq!(iv(1, 2).union(iv(3, 4)), Some(iv(1, 4)));
+        assert_eq!(iv(2, 8).union(iv(3, 7)), Some(iv(2, 8)));
+        assert_eq!(iv(1, 2).union(iv(4, 5)), None);
+        assert_eq!(iv(i32::MIN, i32::MAX).union(Interval::point(0)), Some(iv(i32::MIN, i32::MAX)));
+    }
+
+    #[test]
+    fn difference() {
+        assert_eq!(iv(2, 8).difference(iv(4, 5)), vec![iv(2, 3), iv(6, 8)]);
+        assert_eq!(iv(2, 8).difference(iv(1, 5)), vec![iv(6, 8)]);
+        assert_eq!(iv(2, 8).difference(iv(5, 9)), vec![iv(2, 4)]);
+        assert_eq!(iv(2, 8).difference(iv(2, 8)), vec![]);
+        assert_eq!(iv(3, 7).difference(iv(2, 8)), vec![]);
+        assert_eq!(iv(2, 4).difference(iv(5, 6)), vec![iv(2, 4)]);
+        assert_eq!(iv(2, 8).difference(Interval::point(2)), vec![iv(3, 8)]);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("2-4".parse(), Ok(iv(2, 4)));
+        assert_eq!("-3--1".parse(), Ok(iv(-3, -1)));
+        assert_eq!("-3-1".parse(), Ok(iv(-3, 1)));
+        assert_eq!("5".parse::<Interval>(), Err(ParseIntervalError::Format(String::from("5"))));
+        assert_eq!("a-4".parse::<Interval>(), Err(ParseIntervalError::InvalidBound(String::from("a"))));
+        assert_eq!("4-2".parse::<Interval>(), Err(ParseIntervalError::Reversed(4, 2)));
+        assert_eq!(iv(-3, 1).to_string().parse(), Ok(iv(-3, 1)));
+    }
+}
@@ -1,29 +1,128 @@
-#![allow(dead_code)]
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-
-mod day25;
+#![allow(clippy::needless_return)]
+
+use std::{env, io, process};
+
+use aoc22::{aoc, dashboard, day06, day10, day18, input, solutions, submit};
+
+const USAGE: &str = "\
+usage: aoc22                run the current day
+       aoc22 fetch <day>    download the input of a day into data/ (needs AOC_SESSION)
+       aoc22 submit <day> <part> [answer]
+                            send the computed (or given) answer and record the verdict
+       aoc22 stream <day> <part> [file]
+                            solve a line-oriented day reading the file (or stdin) as it goes
+       aoc22 markers <size>...
+                            find the first day 6 markers of these sizes on stdin, in one pass
+       aoc22 serve [port]   browse answers and drawings on http://127.0.0.1:8022/";
+
+const DEFAULT_PORT: u16 = 8022;
+
+fn parse_day(s: &str) -> u32 {
+    return s.parse().unwrap_or_else(|_| exit_with(&format!("invalid day '{}'", s)));
+}
+
+fn parse_part(s: &str) -> u32 {
+    return match s.parse() {
+        Ok(part @ (1 | 2)) => part,
+        _ => exit_with(&format!("invalid part '{}', expected 1 or 2", s)),
+    };
+}
+
+fn submit_answer(day: u32, part: u32, answer: Option<&str>) {
+    let answer = match answer {
+        Some(a) => String::from(a),
+        None => solutions::solve(day, part)
+            .unwrap_or_else(|| exit_with(&format!("Day {} part {} is not solved yet.", day, part))),
+    };
+    let client = aoc::Client::from_env();
+    let mut history = submit::History::load(client.data_dir())
+        .unwrap_or_else(|err| exit_with(&format!("Could not read the history: {}", err)));
+
+    match submit::submit(&client, &mut history, day, part, &answer) {
+        Ok(verdict) => println!("{}: {}", answer.trim(), verdict),
+        Err(err) => exit_with(&format!("Did not submit the answer of day {} part {}: {}", day, part, err)),
+    }
+}
+
+fn stream(day: u32, part: u32, path: Option<&str>) {
+    if (day, part) == (10, 2) {
+        // The drawing grows with the input, so its rows are printed as they are drawn
+        let drawn = match path {
+            Some(path) => day10::draw_reader(input::open(path), &mut io::stdout().lock()),
+            None => day10::draw_reader(io::stdin().lock(), &mut io::stdout().lock()),
+        };
+        if let Err(err) = drawn {
+            exit_with(&format!("Could not read the input: {}", err));
+        }
+        println!();
+        return;
+    }
+    let solver = solutions::stream_solver(day, part)
+        .unwrap_or_else(|| exit_with(&format!("Day {} part {} cannot be solved as a stream.", day, part)));
+    let answer = match path {
+        Some(path) => solver(&mut input::open(path)),
+        None => solver(&mut io::stdin().lock()),
+    };
+    match answer {
+        Ok(answer) => println!("{}", answer),
+        Err(err) => exit_with(&format!("Could not read the input: {}", err)),
+    }
+}
+
+fn markers(sizes: &[&str]) {
+    let sizes: Vec<usize> = sizes.iter()
+        .map(|s| match s.parse() {
+            Ok(size) if size > 0 => size,
+            _ => exit_with(&format!("invalid marker size '{}'", s)),
+        })
+        .collect();
+    let found = |size, position| println!("{} different characters after {} characters", size, position);
+    match day06::first_markers(io::stdin().lock(), &sizes, found) {
+        Ok(markers) => {
+            for (size, _) in markers.iter().filter(|(_, position)| position.is_none()) {
+                println!("no marker of {} different characters", size);
+            }
+        },
+        Err(err) => exit_with(&format!("Could not read the input: {}", err)),
+    }
+}
+
+fn serve(port: u16) {
+    if let Err(err) = dashboard::serve(port) {
+        exit_with(&format!("Could not serve the dashboard: {}", err));
+    }
+}
+
+fn exit_with(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    process::exit(1);
+}
 
 fn main() {
-    let res = day18::main();
-    println!("Result of the day: {}", res);
-    // let res_bonus = day15::main_bonus();
-    // println!("Bonus result of the day: {}", res_bonus);
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        [] => {
+            let res = day18::main();
+            println!("Result of the day: {}", res);
+            // let res_bonus = day15::main_bonus();
+            // println!("Bonus result of the day: {}", res_bonus);
+        },
+        ["fetch", day] => {
+            let client = aoc::Client::from_env();
+            match client.fetch_input(parse_day(day)) {
+                Ok(path) => println!("Input available at {}", path.display()),
+                Err(err) => exit_with(&format!("Could not fetch the input: {}", err)),
+            }
+        },
+        ["submit", day, part] => submit_answer(parse_day(day), parse_part(part), None),
+        ["submit", day, part, answer] => submit_answer(parse_day(day), parse_part(part), Some(answer)),
+        ["stream", day, part] => stream(parse_day(day), parse_part(part), None),
+        ["stream", day, part, path] => stream(parse_day(day), parse_part(part), Some(path)),
+        ["markers", sizes @ ..] if !sizes.is_empty() => markers(sizes),
+        ["serve"] => serve(DEFAULT_PORT),
+        ["serve", port] => serve(port.parse().unwrap_or_else(|_| exit_with(&format!("invalid port '{}'", port)))),
+        _ => exit_with(USAGE),
+    }
 }
@@ -0,0 +1,90 @@
+use crate::{
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10,
+    day11, day12, day13, day14, day15, day17, day18, day25,
+};
+use crate::input::Input;
+use std::io::{self, BufRead};
+
+pub type Solver = fn(&Input) -> String;
+pub type StreamSolver = fn(&mut dyn BufRead) -> io::Result<String>;
+pub type Render = fn(&Input) -> String;
+
+/// Function computing the answer of a part (1 or 2) of a day from an input, if that part has been solved.
+pub fn solver(day: u32, part: u32) -> Option<Solver> {
+    let solver: Solver = match (day, part) {
+        (1, 1) => |input| day01::solve(input).to_string(),
+        (1, 2) => |input| day01::solve_bonus(input).to_string(),
+        (2, 1) => |input| day02::solve(input).to_string(),
+        (2, 2) => |input| day02::solve_bonus(input).to_string(),
+        (3, 1) => |input| day03::solve(input).to_string(),
+        (3, 2) => |input| day03::solve_bonus(input).to_string(),
+        (4, 1) => |input| day04::solve(input).to_string(),
+        (4, 2) => |input| day04::solve_bonus(input).to_string(),
+        (5, 1) => day05::solve,
+        (5, 2) => day05::solve_bonus,
+        (6, 1) => |input| day06::solve(input).to_string(),
+        (6, 2) => |input| day06::solve_bonus(input).to_string(),
+        (7, 1) => |input| day07::solve(input).to_string(),
+        (7, 2) => |input| day07::solve_bonus(input).to_string(),
+        (8, 1) => |input| day08::solve(input).to_string(),
+        (8, 2) => |input| day08::solve_bonus(input).to_string(),
+        (9, 1) => |input| day09::solve(input).to_string(),
+        (9, 2) => |input| day09::solve_bonus(input).to_string(),
+        (10, 1) => |input| day10::solve(input).to_string(),
+        (10, 2) => day10::solve_bonus,
+        (11, 1) => |input| day11::solve(input).to_string(),
+        (11, 2) => |input| day11::solve_bonus(input).to_string(),
+        (12, 1) => |input| day12::solve(input).to_string(),
+        (12, 2) => |input| day12::solve_bonus(input).to_string(),
+        (13, 1) => |input| day13::solve(input).to_string(),
+        (13, 2) => |input| day13::solve_bonus(input).to_string(),
+        (14, 1) => |input| day14::solve(input).to_string(),
+        (14, 2) => |input| day14::solve_bonus(input).to_string(),
+        (15, 1) => |input| day15::solve(input).to_string(),
+        (15, 2) => |input| day15::solve_bonus(input).to_string(),
+        (17, 1) => |input| day17::solve(input).to_string(),
+        (18, 1) => |input| day18::solve(input).to_string(),
+        (25, 1) => day25::solve,
+        _ => return None,
+    };
+    return Some(solver);
+}
+
+/// Compute the answer of a part (1 or 2) of a day on its puzzle input, if that part has been solved.
+pub fn solve(day: u32, part: u32) -> Option<String> {
+    return solver(day, part).map(|solver| solver(&Input::read_day(day)));
+}
+
+/// Function computing the answer of a part of a day while reading the input, in constant memory.
+/// Only some line-oriented days can do so.
+pub fn stream_solver(day: u32, part: u32) -> Option<StreamSolver> {
+    let solver: StreamSolver = match (day, part) {
+        (1, 1) => |reader| day01::solve_reader(reader).map(|x| x.to_string()),
+        (1, 2) => |reader| day01::solve_bonus_reader(reader).map(|x| x.to_string()),
+        (2, 1) => |reader| day02::solve_reader(reader).map(|x| x.to_string()),
+        (2, 2) => |reader| day02::solve_bonus_reader(reader).map(|x| x.to_string()),
+        (3, 1) => |reader| day03::solve_reader(reader).map(|x| x.to_string()),
+        (3, 2) => |reader| day03::solve_bonus_reader(reader).map(|x| x.to_string()),
+        (4, 1) => |reader| day04::solve_reader(reader).map(|x| x.to_string()),
+        (4, 2) => |reader| day04::solve_bonus_reader(reader).map(|x| x.to_string()),
+        (6, 1) => |reader| day06::solve_reader(reader).map(|x| x.to_string()),
+        (6, 2) => |reader| day06::solve_bonus_reader(reader).map(|x| x.to_string()),
+        (10, 1) => |reader| day10::solve_reader(reader).map(|x| x.to_string()),
+        (10, 2) => |reader| day10::solve_bonus_reader(reader),
+        _ => return None,
+    };
+    return Some(solver);
+}
+
+/// Drawings produced by some days from an input, along with their title.
+pub fn renders(day: u32) -> Vec<(&'static str, Render)> {
+    return match day {
+        5 => vec![("Yard after the CrateMover 9001", day05::render)],
+        7 => vec![("Directory tree", day07::render_tree), ("Directory sizes", day07::render_du)],
+        10 => vec![("CRT screen", day10::solve_bonus)],
+        12 => vec![("Heightmap and shortest path", day12::render)],
+        14 => vec![("Cave filled with sand", day14::render)],
+        17 => vec![("Tower of rocks", day17::render)],
+        _ => Vec::new(),
+    };
+}
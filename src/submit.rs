@@ -0,0 +1,303 @@
+use std::{fmt, fs, io};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::aoc::{Client, ClientError};
+
+const HISTORY_FILE: &str = "submissions.txt";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, but the website did not say in which direction
+    Incorrect,
+    RateLimited,
+}
+
+impl Verdict {
+    fn is_wrong(self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect)
+    }
+
+    /// Read the verdict from the page answered by the website
+    fn from_page(page: &str) -> Option<Self> {
+        if page.contains("That's the right answer") {
+            return Some(Verdict::Correct);
+        }
+        if page.contains("answer too recently") {
+            return Some(Verdict::RateLimited);
+        }
+        if page.contains("That's not the right answer") {
+            if page.contains("too high") {
+                return Some(Verdict::TooHigh);
+            }
+            if page.contains("too low") {
+                return Some(Verdict::TooLow);
+            }
+            return Some(Verdict::Incorrect);
+        }
+        return None;
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Verdict::Correct     => "correct",
+            Verdict::TooHigh     => "too-high",
+            Verdict::TooLow      => "too-low",
+            Verdict::Incorrect   => "incorrect",
+            Verdict::RateLimited => "rate-limited",
+        };
+        return write!(f, "{}", s);
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct"      => Ok(Verdict::Correct),
+            "too-high"     => Ok(Verdict::TooHigh),
+            "too-low"      => Ok(Verdict::TooLow),
+            "incorrect"    => Ok(Verdict::Incorrect),
+            "rate-limited" => Ok(Verdict::RateLimited),
+            _ => Err(format!("unknown verdict '{}'", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Submission {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+impl Submission {
+    /// One submission per line, as `day<TAB>part<TAB>verdict<TAB>answer`
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(4, '\t');
+        let day = fields.next()?.parse().ok()?;
+        let part = fields.next()?.parse().ok()?;
+        let verdict = fields.next()?.parse().ok()?;
+        let answer = String::from(fields.next()?);
+        return Some(Self { day, part, answer, verdict });
+    }
+
+    fn to_line(&self) -> String {
+        return format!("{}\t{}\t{}\t{}", self.day, self.part, self.verdict, self.answer);
+    }
+}
+
+/// Every verdict received so far, stored next to the inputs.
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    pub fn load(data_dir: &Path) -> io::Result<Self> {
+        let path = data_dir.join(HISTORY_FILE);
+        let submissions = match fs::read_to_string(&path) {
+            Ok(contents) => contents.lines().filter_map(Submission::from_line).collect(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        return Ok(Self { path, submissions });
+    }
+
+    fn of_part(&self, day: u32, part: u32) -> impl Iterator<Item = &Submission> {
+        self.submissions.iter().filter(move |s| s.day == day && s.part == part)
+    }
+
+    pub fn correct_answer(&self, day: u32, part: u32) -> Option<&str> {
+        return self.of_part(day, part)
+            .find(|s| s.verdict == Verdict::Correct)
+            .map(|s| s.answer.as_str());
+    }
+
+    /// What is already known about an answer, without asking the website. Besides the answers
+    /// submitted before, a number above a value that was too high is too high as well (same for
+    /// too low), and anything else than the correct answer is incorrect.
+    pub fn known_verdict(&self, day: u32, part: u32, answer: &str) -> Option<Verdict> {
+        if let Some(correct) = self.correct_answer(day, part) {
+            return Some(if correct == answer {Verdict::Correct} else {Verdict::Incorrect});
+        }
+        if let Some(s) = self.of_part(day, part).find(|s| s.answer == answer && s.verdict.is_wrong()) {
+            return Some(s.verdict);
+        }
+
+        let value: i64 = answer.parse().ok()?;
+        for s in self.of_part(day, part) {
+            match (s.verdict, s.answer.parse::<i64>()) {
+                (Verdict::TooHigh, Ok(high)) if value >= high => return Some(Verdict::TooHigh),
+                (Verdict::TooLow,  Ok(low))  if value <= low  => return Some(Verdict::TooLow),
+                _ => (),
+            }
+        }
+        return None;
+    }
+
+    pub fn record(&mut self, submission: Submission) -> io::Result<()> {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", submission.to_line())?;
+        self.submissions.push(submission);
+        return Ok(());
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The answer was not sent, since its verdict is already known
+    AlreadyKnown(Verdict),
+    /// Answers drawn on the screen (e.g. day 10) have to be read and given by hand
+    MultilineAnswer,
+    UnknownResponse,
+    Client(ClientError),
+    Io(io::Error),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubmitError::AlreadyKnown(verdict) => write!(f, "this answer is known to be {}", verdict),
+            SubmitError::MultilineAnswer => write!(f, "the answer spans several lines, read it and give it explicitly"),
+            SubmitError::UnknownResponse => write!(f, "could not find a verdict in the response"),
+            SubmitError::Client(err) => write!(f, "{}", err),
+            SubmitError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<ClientError> for SubmitError {
+    fn from(err: ClientError) -> Self {
+        SubmitError::Client(err)
+    }
+}
+
+impl From<io::Error> for SubmitError {
+    fn from(err: io::Error) -> Self {
+        SubmitError::Io(err)
+    }
+}
+
+/// Send an answer unless its verdict is already known, and record the verdict.
+pub fn submit(
+    client: &Client,
+    history: &mut History,
+    day: u32,
+    part: u32,
+    answer: &str,
+) -> Result<Verdict, SubmitError> {
+    let answer = answer.trim();
+    if answer.contains('\n') {
+        return Err(SubmitError::MultilineAnswer);
+    }
+    if let Some(verdict) = history.known_verdict(day, part, answer) {
+        return Err(SubmitError::AlreadyKnown(verdict));
+    }
+
+    let page = client.post_answer(day, part, answer)?;
+    let verdict = Verdict::from_page(&page).ok_or(SubmitError::UnknownResponse)?;
+    history.record(Submission { day, part, answer: String::from(answer), verdict })?;
+    return Ok(verdict);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{offline_client, serve_once, temp_dir};
+
+    fn history(submissions: &[(&str, Verdict)]) -> History {
+        let submissions = submissions.iter()
+            .map(|&(answer, verdict)| Submission { day: 1, part: 1, answer: String::from(answer), verdict })
+            .collect();
+        return History { path: PathBuf::from(HISTORY_FILE), submissions };
+    }
+
+    #[test]
+    fn verdicts_from_page() {
+        let page = |s: &str| Verdict::from_page(&format!("<article><p>{}</p></article>", s));
+        assert_eq!(page("That's the right answer! You are one gold star closer."), Some(Verdict::Correct));
+        assert_eq!(page("You gave an answer too recently; you have to wait."), Some(Verdict::RateLimited));
+        assert_eq!(page("That's not the right answer; your answer is too high."), Some(Verdict::TooHigh));
+        assert_eq!(page("That's not the right answer; your answer is too low."), Some(Verdict::TooLow));
+        assert_eq!(page("That's not the right answer. If you're stuck, ..."), Some(Verdict::Incorrect));
+        assert_eq!(page("You don't seem to be solving the right level."), None);
+    }
+
+    #[test]
+    fn bounds_from_wrong_answers() {
+        let history = history(&[("100", Verdict::TooHigh), ("10", Verdict::TooLow), ("50", Verdict::Incorrect)]);
+        assert_eq!(history.known_verdict(1, 1, "100"), Some(Verdict::TooHigh));
+        assert_eq!(history.known_verdict(1, 1, "150"), Some(Verdict::TooHigh));
+        assert_eq!(history.known_verdict(1, 1, "10"), Some(Verdict::TooLow));
+        assert_eq!(history.known_verdict(1, 1, "-3"), Some(Verdict::TooLow));
+        assert_eq!(history.known_verdict(1, 1, "50"), Some(Verdict::Incorrect));
+        assert_eq!(history.known_verdict(1, 1, "42"), None);
+        assert_eq!(history.known_verdict(1, 1, "abc"), None);
+        // Other parts know nothing
+        assert_eq!(history.known_verdict(1, 2, "150"), None);
+    }
+
+    #[test]
+    fn correct_answer_already_known() {
+        let mut history = history(&[("12", Verdict::TooLow), ("42", Verdict::Correct)]);
+        assert_eq!(history.known_verdict(1, 1, "42"), Some(Verdict::Correct));
+        assert_eq!(history.known_verdict(1, 1, "43"), Some(Verdict::Incorrect));
+
+        // The answer must not be sent
+        let client = offline_client(Some("cookie"), Path::new("."));
+        let err = submit(&client, &mut history, 1, 1, " 42\n").unwrap_err();
+        assert!(matches!(err, SubmitError::AlreadyKnown(Verdict::Correct)), "{}", err);
+        let err = submit(&client, &mut history, 1, 1, "43").unwrap_err();
+        assert!(matches!(err, SubmitError::AlreadyKnown(Verdict::Incorrect)), "{}", err);
+        assert_eq!(history.submissions.len(), 2);
+    }
+
+    #[test]
+    fn new_answer_sent_and_recorded() {
+        let (base_url, website) = serve_once("<p>That's not the right answer; your answer is too high.</p>");
+        let dir = temp_dir("submit-mock");
+        let client = Client::new(&base_url, Some(String::from("cookie")), &dir);
+        let mut history = History::load(&dir).unwrap();
+        assert_eq!(submit(&client, &mut history, 4, 2, "900").unwrap(), Verdict::TooHigh);
+        let received = website.join().unwrap();
+        assert_eq!(received.url, "/2022/day/4/answer");
+        assert_eq!(received.cookie.as_deref(), Some("session=cookie"));
+        assert_eq!(received.body, "level=2&answer=900");
+
+        // Recorded, so that a larger answer is not sent again
+        let history = History::load(&dir).unwrap();
+        assert_eq!(history.known_verdict(4, 2, "1000"), Some(Verdict::TooHigh));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn history_lines_round_trip() {
+        let line = "10\t2\ttoo-high\tsome answer\twith a tab";
+        let submission = Submission::from_line(line).unwrap();
+        assert_eq!((submission.day, submission.part), (10, 2));
+        assert_eq!(submission.verdict, Verdict::TooHigh);
+        assert_eq!(submission.answer, "some answer\twith a tab");
+        assert_eq!(submission.to_line(), line);
+        assert!(Submission::from_line("10\t2\tmaybe\t5").is_none());
+
+        let dir = temp_dir("submit-round-trip");
+        let mut history = History::load(&dir).unwrap();
+        assert!(history.submissions.is_empty());
+        history.record(submission).unwrap();
+        history.record(Submission { day: 3, part: 1, answer: String::from("157"), verdict: Verdict::Correct }).unwrap();
+        let loaded = History::load(&dir).unwrap();
+        let lines: Vec<String> = loaded.submissions.iter().map(Submission::to_line).collect();
+        assert_eq!(lines, vec![line, "3\t1\tcorrect\t157"]);
+        assert_eq!(loaded.correct_answer(3, 1), Some("157"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,48 @@
+//! Helpers shared by the tests of the website client and of the submissions.
+
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::{env, fs, process};
+
+use crate::aoc::Client;
+
+/// Empty directory for the files of a test, e.g. inputs or the submission history
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc22-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    return dir;
+}
+
+/// Client of a server which does not exist: nothing listens there, so any request fails
+pub fn offline_client(session: Option<&str>, data_dir: &Path) -> Client {
+    return Client::new("http://127.0.0.1:9", session.map(String::from), data_dir);
+}
+
+/// What the website stand-in received
+#[derive(Debug, PartialEq, Eq)]
+pub struct Received {
+    pub url: String,
+    pub cookie: Option<String>,
+    pub body: String,
+}
+
+/// Local stand-in for the website, answering a single request with `page`. Returns its base
+/// URL, and the thread giving what it received once it has answered.
+pub fn serve_once(page: &str) -> (String, JoinHandle<Received>) {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/2022", server.server_addr().to_ip().unwrap());
+    let page = String::from(page);
+    let website = thread::spawn(move || {
+        let mut request = server.recv().unwrap();
+        let url = String::from(request.url());
+        let cookie = request.headers().iter()
+            .find(|h| h.field.equiv("Cookie"))
+            .map(|h| h.value.to_string());
+        let mut body = String::new();
+        request.as_reader().read_to_string(&mut body).unwrap();
+        request.respond(tiny_http::Response::from_string(page)).unwrap();
+        return Received { url, cookie, body };
+    });
+    return (base_url, website);
+}
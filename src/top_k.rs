@@ -0,0 +1,47 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Keeps the `k` largest items of a stream, in O(log k) per item and O(k) memory.
+#[derive(Clone, Debug)]
+pub struct TopK<T: Ord> {
+    k: usize,
+    /// Min-heap, so that the smallest of the kept items is the one to drop
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> Self {
+        Self { k, heap: BinaryHeap::with_capacity(k + 1) }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(item));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if item > smallest.0 {
+                *smallest = Reverse(item);
+            }
+        }
+    }
+
+    /// Kept items, from largest to smallest
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        // The ordering of `Reverse` makes this sort decreasing
+        return self.heap.into_sorted_vec().into_iter().map(|r| r.0).collect();
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+/// The `k` largest items, from largest to smallest
+pub fn top_k<T: Ord, I: IntoIterator<Item = T>>(items: I, k: usize) -> Vec<T> {
+    let mut top = TopK::new(k);
+    top.extend(items);
+    return top.into_sorted_vec();
+}